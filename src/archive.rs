@@ -0,0 +1,51 @@
+//! Archiving of directories shared as a single document.
+
+use crate::error::{Result, ShareError};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::Write;
+use std::path::Path;
+
+/// A tar archive of `dir`, gzip-compressed if `compress` is set.
+///
+/// Entries are stored under the directory's name, so the archive unpacks into a folder.
+pub fn tar(dir: &Path, compress: bool) -> Result<Vec<u8>> {
+    let error = |message: String| ShareError::InputFile {
+        path: dir.to_path_buf(),
+        message,
+    };
+    if !dir.is_dir() {
+        return Err(error("not a directory".to_string()));
+    }
+
+    let archive = || -> std::io::Result<Vec<u8>> {
+        let mut builder = tar::Builder::new(Vec::new());
+        builder.append_dir_all(dir_name(dir), dir)?;
+        let tar = builder.into_inner()?;
+        if !compress {
+            return Ok(tar);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&tar)?;
+        encoder.finish()
+    };
+    archive().map_err(|err| error(err.to_string()))
+}
+
+/// The file name of the archive of `dir`.
+pub fn file_name(dir: &Path, compress: bool) -> String {
+    format!(
+        "{}.{}",
+        dir_name(dir),
+        if compress { "tar.gz" } else { "tar" }
+    )
+}
+
+/// The name of `dir`, resolving `.` and similar to the actual directory name.
+fn dir_name(dir: &Path) -> String {
+    let resolved = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    resolved
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "archive".to_string())
+}
@@ -0,0 +1,487 @@
+//! Copying share links to, and reading content from, the system clipboard.
+//!
+//! Over SSH the system clipboard is that of the remote machine, so links are instead sent to the
+//! local terminal as an OSC 52 escape sequence, which terminals that support it place on the
+//! clipboard of the machine they run on. Under WSL, where arboard often cannot reach the Windows
+//! clipboard, `clip.exe` is used. Where neither fits, a [`Provider`] can pipe the link into a
+//! clipboard tool such as `wl-copy` or `xclip`, or any command.
+
+use crate::error::{Result, ShareError};
+use arboard::Clipboard;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::Duration;
+
+/// Something copied text can be placed on.
+pub trait ClipboardProvider {
+    /// Place `text` on the clipboard.
+    fn copy(&self, text: &str) -> Result<()>;
+}
+
+/// The system clipboard, through arboard. See [`copy`].
+pub struct ArboardClipboard;
+
+impl ClipboardProvider for ArboardClipboard {
+    fn copy(&self, text: &str) -> Result<()> {
+        copy(text)
+    }
+}
+
+/// The terminal's clipboard, through an OSC 52 escape sequence. See [`osc52`].
+pub struct Osc52Clipboard;
+
+impl ClipboardProvider for Osc52Clipboard {
+    fn copy(&self, text: &str) -> Result<()> {
+        osc52(text)
+    }
+}
+
+/// The Windows clipboard from WSL. See [`wsl`].
+pub struct WslClipboard;
+
+impl ClipboardProvider for WslClipboard {
+    fn copy(&self, text: &str) -> Result<()> {
+        wsl(text)
+    }
+}
+
+/// A command that reads the text to copy from its stdin.
+pub struct CommandClipboard {
+    /// How the command is referred to in errors.
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl CommandClipboard {
+    /// `command` run by the shell, so it can hold arguments and pipes.
+    pub fn shell(command: &str) -> Self {
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        Self {
+            name: command.to_string(),
+            program: shell.to_string(),
+            args: vec![flag.to_string(), command.to_string()],
+        }
+    }
+}
+
+impl ClipboardProvider for CommandClipboard {
+    fn copy(&self, text: &str) -> Result<()> {
+        let args: Vec<&str> = self.args.iter().map(String::as_str).collect();
+        pipe(&self.name, &self.program, &args, text.as_bytes())
+    }
+}
+
+/// Where copied text goes, as chosen with `--clipboard` or the `clipboard` setting.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Provider {
+    /// OSC 52 in an SSH session, the Windows clipboard under WSL, the system clipboard otherwise.
+    #[default]
+    Auto,
+    /// The system clipboard, through arboard.
+    Arboard,
+    /// An OSC 52 escape sequence written to the terminal.
+    Osc52,
+    /// The Windows clipboard from WSL, through `clip.exe` or PowerShell.
+    Wsl,
+    /// `wl-copy`, for Wayland.
+    WlCopy,
+    /// `xclip`, for X11.
+    Xclip,
+    /// `pbcopy`, for macOS.
+    Pbcopy,
+    /// A shell command given as `command:<cmd>`, which reads the text from its stdin.
+    Command(String),
+}
+
+impl Provider {
+    pub const VALUES: &'static [&'static str] = &[
+        "auto",
+        "arboard",
+        "osc52",
+        "wsl",
+        "wl-copy",
+        "xclip",
+        "pbcopy",
+        "command:<cmd>",
+    ];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "auto" => Ok(Provider::Auto),
+            "arboard" => Ok(Provider::Arboard),
+            "osc52" => Ok(Provider::Osc52),
+            "wsl" => Ok(Provider::Wsl),
+            "wl-copy" => Ok(Provider::WlCopy),
+            "xclip" => Ok(Provider::Xclip),
+            "pbcopy" => Ok(Provider::Pbcopy),
+            _ => match value.strip_prefix("command:").map(str::trim) {
+                Some(command) if !command.is_empty() => Ok(Provider::Command(command.to_string())),
+                _ => Err(ShareError::InvalidConfigValue {
+                    key: "clipboard".to_string(),
+                    value: value.to_string(),
+                    expected: Self::VALUES.join(", "),
+                }),
+            },
+        }
+    }
+
+    /// The provider used here: [`Provider::Auto`] picks OSC 52 when `SSH_TTY` is set, and the
+    /// Windows clipboard under WSL.
+    pub fn resolve(&self) -> Self {
+        match self {
+            Provider::Auto if std::env::var_os("SSH_TTY").is_some() => Provider::Osc52,
+            Provider::Auto if is_wsl() => Provider::Wsl,
+            Provider::Auto => Provider::Arboard,
+            provider => provider.clone(),
+        }
+    }
+
+    /// The [`ClipboardProvider`] that copies for this provider here.
+    pub fn provider(&self) -> Box<dyn ClipboardProvider> {
+        let command = |program: &str, args: &[&str]| {
+            Box::new(CommandClipboard {
+                name: program.to_string(),
+                program: program.to_string(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+            })
+        };
+        match self.resolve() {
+            Provider::Osc52 => Box::new(Osc52Clipboard),
+            Provider::Wsl => Box::new(WslClipboard),
+            Provider::WlCopy => command("wl-copy", &[]),
+            Provider::Xclip => command("xclip", &["-selection", "clipboard"]),
+            Provider::Pbcopy => command("pbcopy", &[]),
+            Provider::Command(command) => Box::new(CommandClipboard::shell(&command)),
+            _ => Box::new(ArboardClipboard),
+        }
+    }
+
+    /// Place `text` on the clipboard this provider reaches.
+    pub fn copy(&self, text: &str) -> Result<()> {
+        self.provider().copy(text)
+    }
+
+    /// Whether what this provider copied can be read back and cleared, as [`clear_after`] does.
+    /// Neither is possible through OSC 52, from WSL, or for a command.
+    pub fn can_clear(&self) -> bool {
+        matches!(
+            self.resolve(),
+            Provider::Arboard | Provider::WlCopy | Provider::Xclip | Provider::Pbcopy
+        )
+    }
+
+    /// The text on the clipboard this provider reaches.
+    fn paste(&self) -> Result<String> {
+        match self.resolve() {
+            Provider::WlCopy => capture("wl-paste", &["--no-newline"]),
+            Provider::Xclip => capture("xclip", &["-selection", "clipboard", "-o"]),
+            Provider::Pbcopy => capture("pbpaste", &[]),
+            _ => paste(),
+        }
+    }
+
+    /// Empty the clipboard this provider reaches.
+    fn clear(&self) -> Result<()> {
+        match self.resolve() {
+            Provider::WlCopy => pipe("wl-copy", "wl-copy", &["--clear"], &[]),
+            // xclip and pbcopy cannot clear, the X11 and macOS clipboards they reach can
+            _ => {
+                Clipboard::new()?.clear()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provider::Auto => f.write_str("auto"),
+            Provider::Arboard => f.write_str("arboard"),
+            Provider::Osc52 => f.write_str("osc52"),
+            Provider::Wsl => f.write_str("wsl"),
+            Provider::WlCopy => f.write_str("wl-copy"),
+            Provider::Xclip => f.write_str("xclip"),
+            Provider::Pbcopy => f.write_str("pbcopy"),
+            Provider::Command(command) => write!(f, "command:{}", command),
+        }
+    }
+}
+
+/// The hidden subcommand that clears the clipboard in the background. See [`clear_after`].
+pub const CLEAR_COMMAND: &str = "clear-clipboard";
+
+/// The hidden subcommand that keeps copied text on the clipboard in the background. See [`copy`].
+pub const HOLD_COMMAND: &str = "hold-clipboard";
+
+/// Place `text` on the system clipboard.
+///
+/// On Wayland and X11 the clipboard is served by the process that set it, and empties when that
+/// process exits. There the text is handed to a detached process running [`HOLD_COMMAND`], which
+/// serves it until another application takes the clipboard over.
+pub fn copy(text: &str) -> Result<()> {
+    if cfg!(all(unix, not(target_os = "macos"))) {
+        // Fail here when there is no clipboard, rather than unnoticed in the background
+        Clipboard::new()?;
+        let mut child = helper(HOLD_COMMAND)?.stdin(Stdio::piped()).spawn()?;
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(text.as_bytes())?;
+        return Ok(());
+    }
+    let mut clipboard = Clipboard::new()?;
+    clipboard.set_text(text)?;
+    Ok(())
+}
+
+/// Place the text read from stdin on the system clipboard, and keep serving it until another
+/// application takes the clipboard over, as started by [`copy`].
+pub fn hold_stdin() -> Result<()> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    let mut clipboard = Clipboard::new()?;
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        use arboard::SetExtLinux;
+        clipboard.set().wait().text(text)?;
+    }
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    clipboard.set_text(text)?;
+    Ok(())
+}
+
+/// Send `text` to the terminal's clipboard as an OSC 52 sequence.
+///
+/// The sequence is written to the controlling terminal rather than stdout, which may be piped.
+/// tmux passes it on to the outer terminal only when wrapped in its own escape sequence.
+pub fn osc52(text: &str) -> Result<()> {
+    let sequence = format!("\x1b]52;c;{}\x07", BASE64.encode(text));
+    let sequence = if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    };
+    OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .and_then(|mut tty| tty.write_all(sequence.as_bytes()))
+        .map_err(ShareError::Osc52)
+}
+
+/// Place `text` on the Windows clipboard from WSL, through `clip.exe`, or PowerShell when it is
+/// not on the PATH.
+pub fn wsl(text: &str) -> Result<()> {
+    // clip.exe reads text in the console code page unless it starts with a byte order mark
+    let utf16: Vec<u8> = std::iter::once(0xfeff)
+        .chain(text.encode_utf16())
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    match pipe("clip.exe", "clip.exe", &[], &utf16) {
+        Err(ShareError::ClipboardCommand { message, .. }) if message == NOT_FOUND => pipe(
+            "powershell.exe",
+            "powershell.exe",
+            &[
+                "-NoProfile",
+                "-Command",
+                "[Console]::InputEncoding = [Text.Encoding]::UTF8; Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+            ],
+            text.as_bytes(),
+        ),
+        result => result,
+    }
+}
+
+/// Whether this is Linux running under the Windows Subsystem for Linux.
+pub fn is_wsl() -> bool {
+    cfg!(target_os = "linux")
+        && std::fs::read_to_string("/proc/version")
+            .is_ok_and(|version| version.to_lowercase().contains("microsoft"))
+}
+
+const NOT_FOUND: &str = "not found";
+
+/// Run `program` with `input` on its stdin, failing with [`ShareError::ClipboardCommand`] for
+/// the command `name`.
+///
+/// Tools such as `xclip` and `wl-copy` leave a process behind that serves the clipboard and
+/// inherits their output, so stderr goes to a file instead of a pipe that would stay open.
+fn pipe(name: &str, program: &str, args: &[&str], input: &[u8]) -> Result<()> {
+    let error = |message: String| command_error(name, message);
+    let mut stderr = tempfile::tempfile().map_err(|err| error(err.to_string()))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(stderr.try_clone().map_err(|err| error(err.to_string()))?)
+        .spawn()
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => error(NOT_FOUND.to_string()),
+            _ => error(err.to_string()),
+        })?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input)
+        .map_err(|err| error(err.to_string()))?;
+    let status = child.wait().map_err(|err| error(err.to_string()))?;
+    let mut output = Vec::new();
+    stderr.seek(SeekFrom::Start(0))?;
+    stderr.read_to_end(&mut output)?;
+    check_status(name, status, &output)
+}
+
+/// Run `program` and return what it prints, failing with [`ShareError::ClipboardCommand`].
+fn capture(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => command_error(program, NOT_FOUND.to_string()),
+            _ => command_error(program, err.to_string()),
+        })?;
+    check_status(program, output.status, &output.stderr)?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Fail with `stderr` of the command `name` if it exited with an unsuccessful `status`.
+fn check_status(name: &str, status: ExitStatus, stderr: &[u8]) -> Result<()> {
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(stderr).trim().to_string();
+        return Err(command_error(
+            name,
+            if stderr.is_empty() {
+                status.to_string()
+            } else {
+                stderr
+            },
+        ));
+    }
+    Ok(())
+}
+
+fn command_error(name: &str, message: String) -> ShareError {
+    ShareError::ClipboardCommand {
+        command: name.to_string(),
+        message,
+    }
+}
+
+/// Clear the clipboard `provider` copied `text` to `timeout` from now if it still holds `text`,
+/// from a detached process that outlives this one. Only for providers that
+/// [can clear](Provider::can_clear).
+///
+/// The process is this binary running [`CLEAR_COMMAND`], and is only told the digest of `text`,
+/// so the link does not show up in the process list.
+pub fn clear_after(provider: &Provider, text: &str, timeout: Duration) -> Result<()> {
+    helper(CLEAR_COMMAND)?
+        .arg("--provider")
+        .arg(provider.resolve().to_string())
+        .arg("--after")
+        .arg(timeout.as_secs().to_string())
+        .arg("--digest")
+        .arg(digest(text))
+        .spawn()?;
+    Ok(())
+}
+
+/// This binary running the hidden `subcommand`, detached from the terminal.
+fn helper(subcommand: &str) -> Result<Command> {
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg(subcommand)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Out of the terminal's process group, so Ctrl-C in the shell does not stop it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    Ok(command)
+}
+
+/// Wait `after`, then clear the clipboard `provider` reaches if the text on it has `digest`, as
+/// left by [`clear_after`]. Anything copied in the meantime is left alone.
+pub fn clear_if_unchanged(
+    provider: &Provider,
+    digest_of_text: &str,
+    after: Duration,
+) -> Result<()> {
+    std::thread::sleep(after);
+    if provider
+        .paste()
+        .is_ok_and(|text| digest(&text) == digest_of_text)
+    {
+        provider.clear()?;
+    }
+    Ok(())
+}
+
+fn digest(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Check that the system clipboard can be reached.
+pub fn check() -> Result<()> {
+    Clipboard::new()?;
+    Ok(())
+}
+
+/// Whether there is no display server to hold a clipboard, as in an SSH session or a container.
+pub fn is_headless() -> bool {
+    cfg!(all(unix, not(target_os = "macos")))
+        && ["DISPLAY", "WAYLAND_DISPLAY"]
+            .iter()
+            .all(|var| std::env::var_os(var).is_none_or(|value| value.is_empty()))
+}
+
+/// The text on the system clipboard.
+pub fn paste() -> Result<String> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(ShareError::ClipboardRead)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn pipe_returns_while_a_forked_server_keeps_running() {
+        let start = Instant::now();
+        pipe("sh", "sh", &["-c", "sleep 5 & cat > /dev/null"], b"link").unwrap();
+        assert!(start.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn pipe_reports_stderr_of_a_failing_command() {
+        let err = pipe("tool", "sh", &["-c", "echo oops >&2; exit 1"], b"").unwrap_err();
+        assert!(
+            matches!(err, ShareError::ClipboardCommand { command, message } if command == "tool" && message == "oops")
+        );
+    }
+
+    #[test]
+    fn pipe_reports_a_missing_program() {
+        let err = pipe("missing", "share-1password-missing-tool", &[], b"").unwrap_err();
+        assert!(
+            matches!(err, ShareError::ClipboardCommand { message, .. } if message == NOT_FOUND)
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! The owners of a path according to the repository's `CODEOWNERS` file.
+
+use crate::error::{Result, ShareError};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+
+/// Where GitHub looks for the `CODEOWNERS` file, relative to the repository root, in order.
+pub const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// The rules of a `CODEOWNERS` file.
+#[derive(Debug, Clone)]
+pub struct Codeowners {
+    path: PathBuf,
+    /// Each pattern with its owners, in file order.
+    rules: Vec<(Gitignore, Vec<String>)>,
+}
+
+impl Codeowners {
+    /// Load the `CODEOWNERS` file of the repository at `root`, if it has one.
+    pub fn find(root: &Path) -> Result<Option<Self>> {
+        match LOCATIONS
+            .iter()
+            .map(|location| root.join(location))
+            .find(|path| path.is_file())
+        {
+            Some(path) => Self::load(&path, root).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Load the `CODEOWNERS` file at `path`, whose patterns are relative to `root`.
+    pub fn load(path: &Path, root: &Path) -> Result<Self> {
+        let error = |message: String| ShareError::InputFile {
+            path: path.to_path_buf(),
+            message,
+        };
+        let contents = std::fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+
+        let mut rules = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.split_once('#').map_or(line, |(rule, _)| rule).trim();
+            let mut words = line.split_whitespace();
+            let Some(pattern) = words.next() else {
+                continue;
+            };
+            let mut builder = GitignoreBuilder::new(root);
+            builder
+                .add_line(None, pattern)
+                .map_err(|err| error(format!("line {}: {}", index + 1, err)))?;
+            let matcher = builder
+                .build()
+                .map_err(|err| error(format!("line {}: {}", index + 1, err)))?;
+            rules.push((matcher, words.map(str::to_string).collect()));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            rules,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The owners of `path`, an absolute path within the repository, given by the last rule
+    /// matching it. A rule without owners leaves the path unowned.
+    pub fn owners(&self, path: &Path) -> Vec<String> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(matcher, _)| {
+                matcher
+                    .matched_path_or_any_parents(path, path.is_dir())
+                    .is_ignore()
+            })
+            .map(|(_, owners)| owners.clone())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,117 @@
+//! `share-1password batch`: make every share listed in a manifest.
+
+use super::share::{
+    enforce_policy, file_builder, files_title, github_outputs, notify, record, Shared,
+};
+use super::Context;
+use crate::cli::{BatchArgs, OutputFormat};
+use crate::table;
+use share_1password::git::RepoInfo;
+use share_1password::manifest::{Manifest, ManifestEntry};
+use share_1password::op::OpBackend;
+use share_1password::{info, warn, Result, ShareResult};
+use std::path::Path;
+
+pub fn run(ctx: &Context, args: BatchArgs) -> Result<()> {
+    let manifest = Manifest::load(&args.manifest)?;
+    if manifest.shares.is_empty() {
+        info!("The manifest lists no shares.");
+        return Ok(());
+    }
+    let repo = if args.no_git {
+        None
+    } else {
+        let dir = args
+            .manifest
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty());
+        RepoInfo::detect(dir.unwrap_or(Path::new(".")))
+    };
+
+    let backend = ctx.backend();
+    let mut rows = Vec::new();
+    let mut shared = Vec::new();
+    let mut first_error = None;
+    let mut failures = 0;
+    for entry in &manifest.shares {
+        let vault = ctx.vault(entry.vault.clone());
+        let result = share(ctx, backend.as_ref(), entry, &vault, repo.clone());
+        let status = match result {
+            Ok(result) => {
+                record(&result);
+                let link = result.link.trim().to_string();
+                shared.push(result);
+                link
+            }
+            Err(err) => {
+                super::print_failure(&entry.file.display().to_string(), &err, args.output);
+                failures += 1;
+                first_error.get_or_insert(err);
+                "failed".to_string()
+            }
+        };
+        rows.push(vec![entry.file.display().to_string(), vault, status]);
+    }
+    match args.output {
+        OutputFormat::Text => table::print(&["FILE", "VAULT", "LINK"], &rows),
+        OutputFormat::Json => {
+            let shared: Vec<Shared> = shared.iter().map(Shared::from).collect();
+            println!("{}", serde_json::to_string_pretty(&shared)?);
+        }
+        OutputFormat::GithubActions if !shared.is_empty() => {
+            github_outputs(&shared.iter().map(Shared::from).collect::<Vec<_>>())?
+        }
+        OutputFormat::GithubActions => {}
+    }
+    if args.notify && !shared.is_empty() {
+        notify(&shared.iter().map(Shared::from).collect::<Vec<_>>());
+    }
+
+    match first_error {
+        Some(err) => {
+            if args.output == OutputFormat::Text {
+                warn!("{} of {} share(s) failed.", failures, rows.len());
+            }
+            Err(err)
+        }
+        None => Ok(()),
+    }
+}
+
+fn share(
+    ctx: &Context,
+    backend: &dyn OpBackend,
+    entry: &ManifestEntry,
+    vault: &str,
+    repo: Option<RepoInfo>,
+) -> Result<ShareResult> {
+    let title = match &entry.title {
+        Some(title) => title.clone(),
+        None => {
+            let name = entry.file.file_name().unwrap_or(entry.file.as_os_str());
+            files_title(&ctx.config, &[name.to_string_lossy().into_owned()])?
+        }
+    };
+    let mut builder = file_builder(std::slice::from_ref(&entry.file))?
+        .vault(vault)
+        .expires_in(ctx.expires_in(entry.expires_in.clone())?)
+        .recipients(ctx.recipients(entry.emails.clone(), backend)?)
+        .title(title)
+        .tags(
+            entry
+                .tags
+                .iter()
+                .map(|tag| tag.trim())
+                .filter(|tag| !tag.is_empty()),
+        )
+        .repo(repo);
+    if let Some(notes) = entry
+        .notes
+        .as_ref()
+        .filter(|notes| !notes.trim().is_empty())
+    {
+        builder = builder.notes(notes.clone());
+    }
+    enforce_policy(&ctx.config, &builder, None)?;
+    builder.share_with(backend)
+}
@@ -0,0 +1,16 @@
+//! `share-1password completions`: print a shell completion script.
+
+use crate::cli::{Cli, CompletionsArgs};
+use clap::CommandFactory;
+use share_1password::Result;
+use std::io::Write;
+
+pub fn run(args: CompletionsArgs) -> Result<()> {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    // Generated into a buffer, as clap_complete panics when it cannot write
+    let mut script = Vec::new();
+    clap_complete::generate(args.shell, &mut command, name, &mut script);
+    std::io::stdout().write_all(&script)?;
+    Ok(())
+}
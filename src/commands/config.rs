@@ -0,0 +1,163 @@
+//! `share-1password config`: create and edit the global configuration file.
+
+use super::{theme, Context};
+use crate::cli::{Backend, ConfigCommand};
+use dialoguer::{Input, Select};
+use share_1password::config::{self, Config};
+use share_1password::op::OpBackend;
+use share_1password::share::{DEFAULT_EXPIRES_IN, DEFAULT_VAULT};
+use share_1password::{expiry, info, warn, Result, ShareError};
+use std::path::PathBuf;
+
+/// Run a `config` subcommand. `profile` selects the profile section that `get` and `set` act on,
+/// and `account` the account `init` lists vaults from.
+pub fn run(
+    command: ConfigCommand,
+    profile: Option<&str>,
+    backend: Backend,
+    account: Option<String>,
+) -> Result<()> {
+    match command {
+        ConfigCommand::Init => {
+            let config = Config::load()?.merge(Config::from_env());
+            let ctx = Context::new(config, backend);
+            init(ctx.backend_for(account).as_ref())
+        }
+        ConfigCommand::Get { key } => get(&key, profile),
+        ConfigCommand::Set { key, value } => set(&key, &value, profile),
+        ConfigCommand::Path => print_path(),
+    }
+}
+
+fn path() -> Result<PathBuf> {
+    config::global_path().ok_or_else(|| ShareError::Config {
+        path: PathBuf::from(config::CONFIG_FILE),
+        message: "Could not determine the configuration directory. Set HOME or XDG_CONFIG_HOME."
+            .to_string(),
+    })
+}
+
+/// Print the path of the global configuration file.
+fn print_path() -> Result<()> {
+    println!("{}", path()?.display());
+    Ok(())
+}
+
+/// Print the value of `key`, from `profile` if given.
+fn get(key: &str, profile: Option<&str>) -> Result<()> {
+    let config = Config::load_from(&path()?)?;
+    let section = match profile {
+        Some(profile) => {
+            config
+                .profiles
+                .get(profile)
+                .cloned()
+                .ok_or_else(|| ShareError::UnknownProfile {
+                    profile: profile.to_string(),
+                    available: config
+                        .profiles
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                })?
+        }
+        None => config,
+    };
+    if let Some(value) = section.get(key)? {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Set `key` to `value`, in `profile` if given, and save the file.
+fn set(key: &str, value: &str, profile: Option<&str>) -> Result<()> {
+    let path = path()?;
+    let mut config = Config::load_from(&path)?;
+    match profile {
+        Some(profile) => config
+            .profiles
+            .entry(profile.to_string())
+            .or_default()
+            .set(key, value)?,
+        None => config.set(key, value)?,
+    }
+    config.save_to(&path)
+}
+
+/// Interactively choose the default vault, expiry, and recipients and save them.
+fn init(backend: &dyn OpBackend) -> Result<()> {
+    let path = path()?;
+    let mut config = Config::load_from(&path)?;
+    let theme = theme();
+
+    let current_vault = config
+        .vault
+        .clone()
+        .unwrap_or_else(|| DEFAULT_VAULT.to_string());
+    let vault_names = match backend.list_vaults() {
+        Ok(vaults) => vaults.into_iter().map(|vault| vault.name).collect(),
+        Err(err) => {
+            warn!(
+                "Could not list vaults, enter the vault name manually.\n{}",
+                err
+            );
+            Vec::new()
+        }
+    };
+    let vault = if vault_names.is_empty() {
+        Input::with_theme(&*theme)
+            .with_prompt("Default vault")
+            .default(current_vault)
+            .interact_text()?
+    } else {
+        let mut choices = vault_names.clone();
+        choices.push("Another vault...".to_string());
+        let default = vault_names
+            .iter()
+            .position(|name| *name == current_vault)
+            .unwrap_or(choices.len() - 1);
+        let selection = Select::with_theme(&*theme)
+            .with_prompt("Default vault")
+            .items(&choices)
+            .default(default)
+            .interact()?;
+        if selection < vault_names.len() {
+            vault_names[selection].clone()
+        } else {
+            Input::with_theme(&*theme)
+                .with_prompt("Vault name (created on first share if missing)")
+                .default(current_vault)
+                .interact_text()?
+        }
+    };
+
+    let expires_in: String = Input::with_theme(&*theme)
+        .with_prompt("Default link expiry")
+        .default(
+            config
+                .expires_in
+                .clone()
+                .unwrap_or_else(|| DEFAULT_EXPIRES_IN.to_string()),
+        )
+        .validate_with(|input: &String| {
+            expiry::parse(input)
+                .map(|_| ())
+                .ok_or("Use e.g. 7d, 36h, \"2 weeks\", or \"tomorrow\"")
+        })
+        .interact_text()?;
+
+    let emails: String = Input::with_theme(&*theme)
+        .with_prompt("Default recipients (comma separated, empty for anyone with the link)")
+        .with_initial_text(config.get("emails")?.unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    config.set("vault", &vault)?;
+    config.set("expires_in", &expires_in)?;
+    config.set("emails", &emails)?;
+    config.save_to(&path)?;
+
+    info!("Configuration written to {}", path.display());
+    Ok(())
+}
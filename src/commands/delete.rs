@@ -0,0 +1,51 @@
+//! `share-1password delete`: remove an item created by this tool.
+
+use super::{can_prompt, theme, Context};
+use crate::cli::DeleteArgs;
+use dialoguer::Confirm;
+use share_1password::items;
+use share_1password::op::DeleteMode;
+use share_1password::{info, output, Result, ShareError};
+
+pub fn run(ctx: &Context, args: DeleteArgs) -> Result<()> {
+    let vault = ctx.vault(args.vault);
+    let mode = if args.permanent {
+        DeleteMode::Permanent
+    } else {
+        DeleteMode::Archive
+    };
+
+    let backend = ctx.backend();
+    backend.ensure_signed_in()?;
+    let item = items::find(backend.as_ref(), &args.item, &vault)?;
+
+    if !args.yes && !output::is_unattended() {
+        let action = match mode {
+            DeleteMode::Archive => format!("move '{}' to the archive", item.title),
+            DeleteMode::Permanent => format!("permanently delete '{}'", item.title),
+        };
+        if !can_prompt() {
+            return Err(ShareError::ConfirmationRequired { action });
+        }
+        let prompt = match mode {
+            DeleteMode::Archive => format!("Move '{}' to the archive?", item.title),
+            DeleteMode::Permanent => format!("Permanently delete '{}'?", item.title),
+        };
+        let confirmed = Confirm::with_theme(&*theme())
+            .with_prompt(prompt)
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            info!("Nothing was deleted.");
+            return Ok(());
+        }
+    }
+
+    backend.delete_item(&item.id, &vault, mode)?;
+
+    match mode {
+        DeleteMode::Archive => info!("Moved '{}' to the archive.", item.title),
+        DeleteMode::Permanent => info!("Deleted '{}'.", item.title),
+    }
+    Ok(())
+}
@@ -0,0 +1,286 @@
+//! `share-1password doctor`: check that everything a share needs is in place.
+
+use super::share::Shared;
+use super::Context;
+use crate::cli::Backend;
+use share_1password::clipboard::{self, Provider};
+use share_1password::op::{connect, CliBackend, ConnectBackend, Feature, OpBackend};
+use share_1password::output::{self, Style};
+use share_1password::policy::Policy;
+use share_1password::{expiry, title, Config, Result, ShareError};
+use std::path::Path;
+
+/// The outcome of one check.
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Collects check results, printing each as it comes in.
+#[derive(Default)]
+struct Report {
+    failed: usize,
+}
+
+impl Report {
+    /// Print a result, with `hint` on what to do about it unless it passed.
+    fn check(&mut self, status: Status, message: impl AsRef<str>, hint: Option<&str>) {
+        let label = match status {
+            Status::Pass => Style::Success.stdout("ok   "),
+            Status::Warn => Style::Warning.stdout("warn "),
+            Status::Fail => {
+                self.failed += 1;
+                Style::Error.stdout("FAIL ")
+            }
+        };
+        match (hint, matches!(status, Status::Pass)) {
+            // Plain output keeps each check on one line
+            (Some(hint), false) if output::is_plain() => {
+                println!("{} {} ({})", label, message.as_ref(), hint)
+            }
+            (Some(hint), false) => println!("{} {}\n      {}", label, message.as_ref(), hint),
+            _ => println!("{} {}", label, message.as_ref()),
+        }
+    }
+
+    fn pass(&mut self, message: impl AsRef<str>) {
+        self.check(Status::Pass, message, None);
+    }
+
+    fn warn(&mut self, message: impl AsRef<str>, hint: &str) {
+        self.check(Status::Warn, message, Some(hint));
+    }
+
+    fn fail(&mut self, message: impl AsRef<str>, hint: &str) {
+        self.check(Status::Fail, message, Some(hint));
+    }
+}
+
+/// Run every check, failing with [`ShareError::ChecksFailed`] if any of them did.
+///
+/// The configuration is loaded here rather than by the caller, so that a broken one is reported
+/// like any other problem.
+pub fn run(profile: Option<&str>, backend: Backend, account: Option<String>) -> Result<()> {
+    let mut report = Report::default();
+
+    let config = check_config(&mut report, profile).merge(Config {
+        account,
+        ..Config::default()
+    });
+    let ctx = Context::new(config, backend);
+    if check_op(&mut report) {
+        check_accounts(&mut report, ctx.config.account.as_deref());
+        check_vault(&mut report, &ctx);
+    }
+    if backend == Backend::Connect {
+        check_connect(&mut report);
+    }
+    check_clipboard(&mut report, ctx.config.clipboard().unwrap_or_default());
+
+    match report.failed {
+        0 => Ok(()),
+        failed => Err(ShareError::ChecksFailed { failed }),
+    }
+}
+
+/// Check the configuration files and the values in them, returning what could be loaded.
+fn check_config(report: &mut Report, profile: Option<&str>) -> Config {
+    let config = match Config::load().and_then(|config| config.with_profile(profile)) {
+        Ok(config) => config.merge(Config::from_env()),
+        Err(err) => {
+            report.fail(
+                err.to_string(),
+                "Fix the file, or run `share-1password config init` to write a new one.",
+            );
+            return Config::from_env();
+        }
+    };
+
+    let mut problems = Vec::new();
+    if let Err(err) = config.public_links() {
+        problems.push(err.to_string());
+    }
+    if let Err(err) = config.clipboard() {
+        problems.push(err.to_string());
+    }
+    for (key, value, link) in [
+        ("expires_in", &config.expires_in, true),
+        ("max_expires_in", &config.max_expires_in, true),
+        ("purge_grace", &config.purge_grace, false),
+        ("clipboard_timeout", &config.clipboard_timeout, false),
+    ] {
+        if let Some(value) = value.as_deref() {
+            let valid = if link {
+                expiry::link_expiry(value).is_some()
+            } else {
+                expiry::parse(value).is_some()
+            };
+            if !valid {
+                problems.push(format!("{}: invalid duration '{}'", key, value));
+            }
+        }
+    }
+    if let Some(format) = &config.title_format {
+        if let Err(err) = title::render(format) {
+            problems.push(err.to_string());
+        }
+    }
+    if let Some(template) = &config.output_template {
+        if let Err(err) = Shared::default().message(template) {
+            problems.push(err.to_string());
+        }
+    }
+    if let Some(path) = &config.policy_file {
+        if let Err(err) = Policy::load(Path::new(path)) {
+            problems.push(err.to_string());
+        }
+    }
+
+    if problems.is_empty() {
+        report.pass("Configuration is valid");
+    } else {
+        for problem in problems {
+            report.fail(
+                format!("Configuration: {}", problem),
+                "Correct it with `share-1password config set <key> <value>`.",
+            );
+        }
+    }
+    config
+}
+
+/// Check that `op` is installed and recent enough, returning whether it can be run.
+fn check_op(report: &mut Report) -> bool {
+    let Some(version) = CliBackend::new().version() else {
+        report.fail(
+            "1Password CLI (op) not found",
+            "Install it: https://developer.1password.com/docs/cli/get-started/",
+        );
+        return false;
+    };
+    let required = Feature::ItemShare.min_version();
+    if version < required {
+        report.fail(
+            format!("1Password CLI {} is too old to share items", version),
+            &format!("Update op to {} or later.", required),
+        );
+    } else {
+        report.pass(format!("1Password CLI {}", version));
+    }
+    true
+}
+
+/// Check which of the accounts the CLI knows are signed in, marking the one in use.
+fn check_accounts(report: &mut Report, account: Option<&str>) {
+    let cli = CliBackend::new();
+    if cli.is_service_account() {
+        report.pass("Using a service account (OP_SERVICE_ACCOUNT_TOKEN)");
+        return;
+    }
+
+    let accounts = match cli.list_accounts() {
+        Ok(accounts) => accounts,
+        Err(err) => {
+            report.fail(err.to_string(), "Check that `op account list` works.");
+            return;
+        }
+    };
+    if accounts.is_empty() {
+        report.fail(
+            "No 1Password accounts are set up in the CLI",
+            "Enable Integrate with 1Password CLI in the app's Developer settings, or run `op account add`.",
+        );
+        return;
+    }
+
+    for found in accounts {
+        let in_use = account.is_some_and(|account| {
+            [&found.id, &found.url, &found.email]
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case(account))
+                || found.url.split('.').next() == Some(account)
+        });
+        let name = format!(
+            "{} ({}){}",
+            found.url,
+            found.email,
+            if in_use { ", in use" } else { "" }
+        );
+        match CliBackend::new()
+            .with_account(Some(found.id.clone()))
+            .ensure_signed_in()
+        {
+            Ok(()) => report.pass(format!("Signed in to {}", name)),
+            Err(_) => report.warn(
+                format!("Not signed in to {}", name),
+                &format!("Run `op signin --account {}`.", found.url),
+            ),
+        }
+    }
+}
+
+/// Check that the vault items are stored in can be reached.
+fn check_vault(report: &mut Report, ctx: &Context) {
+    let vault = ctx.vault(None);
+    let backend = ctx.backend();
+    match backend.list_vaults() {
+        Ok(vaults)
+            if vaults
+                .iter()
+                .any(|found| found.name == vault || found.id == vault) =>
+        {
+            report.pass(format!("Vault '{}' is accessible", vault))
+        }
+        Ok(_) if CliBackend::new().is_service_account() => report.fail(
+            format!("Vault '{}' is not accessible to the service account", vault),
+            "Create it in 1Password and grant the service account access.",
+        ),
+        Ok(_) => report.warn(
+            format!("Vault '{}' does not exist", vault),
+            "It is created on the first share, or choose another with `config set vault`.",
+        ),
+        Err(err) => report.fail(
+            format!("Cannot list vaults: {}", err),
+            "Sign in to the account in use with `op signin`.",
+        ),
+    }
+}
+
+/// Check that the 1Password Connect server is configured.
+fn check_connect(report: &mut Report) {
+    match ConnectBackend::from_env() {
+        Some(_) => report.pass("1Password Connect is configured"),
+        None => report.fail(
+            "1Password Connect is not configured",
+            &format!(
+                "Set {} and {}, or use --backend cli.",
+                connect::HOST_ENV,
+                connect::TOKEN_ENV
+            ),
+        ),
+    }
+}
+
+/// Check that share links can be copied to the clipboard. Without one they are only printed, so
+/// this is a warning.
+fn check_clipboard(report: &mut Report, provider: Provider) {
+    match provider.resolve() {
+        Provider::Auto | Provider::Arboard => {}
+        // Whether the terminal honors the sequence cannot be detected
+        Provider::Osc52 => return report.pass("Links are copied through the terminal (OSC 52)"),
+        Provider::Wsl => return report.pass("Links are copied to the Windows clipboard (WSL)"),
+        provider => return report.pass(format!("Links are copied with {}", provider)),
+    }
+    match clipboard::check() {
+        Ok(()) => report.pass("Clipboard is available"),
+        Err(_) if clipboard::is_headless() => report.warn(
+            "Clipboard is unavailable: no display (DISPLAY and WAYLAND_DISPLAY are unset)",
+            "Links are only printed in a headless session; run on a desktop or forward X11.",
+        ),
+        Err(err) => report.warn(
+            format!("Clipboard is unavailable: {}", err),
+            "Install a clipboard provider, e.g. xclip or wl-clipboard.",
+        ),
+    }
+}
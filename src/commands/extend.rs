@@ -0,0 +1,63 @@
+//! `share-1password extend`: hand out a fresh link with a later expiry.
+
+use super::share::{github_outputs, Shared};
+use super::Context;
+use crate::cli::{ExtendArgs, OutputFormat};
+use crate::table::format_time;
+use chrono::Utc;
+use share_1password::history::Record;
+use share_1password::op::ShareOptions;
+use share_1password::{info, items, Result};
+
+pub fn run(ctx: &Context, args: ExtendArgs) -> Result<()> {
+    let vault = ctx.vault(args.vault);
+    let backend = ctx.backend();
+    let options = ShareOptions {
+        expires_in: ctx.expires_in(args.expires_in)?,
+        emails: ctx.recipients(args.emails, backend.as_ref())?,
+        view_once: ctx.view_once(args.view_once),
+    };
+    let delivery = ctx.delivery(&args.delivery)?;
+
+    backend.ensure_signed_in()?;
+    let item = items::find(backend.as_ref(), &args.item, &vault)?;
+    let (link, expires_at) = items::extend(backend.as_ref(), &item, &vault, &options)?;
+
+    super::record_share(Record {
+        item_id: item.id.clone(),
+        vault: vault.clone(),
+        title: item.title.clone(),
+        recipients: options.emails.clone(),
+        expires_at,
+        shared_at: Utc::now(),
+        project: None,
+    });
+
+    let shared = Shared {
+        share_url: link.trim(),
+        item_id: &item.id,
+        vault: &vault,
+        title: &item.title,
+        expires_at,
+        recipients: &options.emails,
+    };
+    let message = delivery.message(&shared)?;
+    let text = message.as_deref().unwrap_or(&link);
+    let copied = delivery.copy(text);
+
+    match delivery.output {
+        OutputFormat::Text => {
+            info!(
+                "New link for '{}', expiring {}{}:",
+                item.title,
+                format_time(expires_at),
+                if copied { ", copied to clipboard" } else { "" }
+            );
+            println!("{}", text);
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&shared)?),
+        OutputFormat::GithubActions => github_outputs(&[shared])?,
+    }
+    delivery.open(&link);
+    Ok(())
+}
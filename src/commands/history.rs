@@ -0,0 +1,177 @@
+//! `share-1password history`: show past shares from the local history store.
+
+use crate::cli::{ExportArgs, ExportFormat, HistoryArgs, HistoryCommand, ListFormat, SortOrder};
+use crate::table;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use share_1password::history::{History, Record};
+use share_1password::{expiry, info, Result, ShareError};
+use std::fs::File;
+use std::io::{self, Write};
+
+/// A record as printed with `--output json`.
+#[derive(Serialize)]
+struct Entry<'a> {
+    #[serde(flatten)]
+    record: &'a Record,
+    status: &'static str,
+}
+
+pub fn run(args: HistoryArgs) -> Result<()> {
+    if let Some(HistoryCommand::Export(export_args)) = args.command {
+        return export(export_args);
+    }
+
+    let records = filter(load()?, &args)?;
+
+    let now = Utc::now();
+    match args.output {
+        ListFormat::Json => {
+            let entries: Vec<Entry> = records
+                .iter()
+                .map(|record| Entry {
+                    record,
+                    status: record.link_status(now).as_str(),
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        ListFormat::Table => {
+            if records.is_empty() {
+                info!("No shares found.");
+                return Ok(());
+            }
+            let rows: Vec<Vec<String>> = records
+                .iter()
+                .map(|record| {
+                    vec![
+                        table::format_time(Some(record.shared_at)),
+                        record.title.clone(),
+                        record.vault.clone(),
+                        if record.recipients.is_empty() {
+                            "anyone with the link".to_string()
+                        } else {
+                            record.recipients.join(", ")
+                        },
+                        table::format_time(record.expires_at),
+                        record.link_status(now).as_str().to_string(),
+                    ]
+                })
+                .collect();
+            table::print(
+                &["SHARED", "TITLE", "VAULT", "RECIPIENTS", "EXPIRES", "LINK"],
+                &rows,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Write the records to `--out` or stdout, oldest first.
+fn export(args: ExportArgs) -> Result<()> {
+    let cutoff = since(args.since.as_deref())?;
+    let mut records: Vec<Record> = load()?
+        .into_iter()
+        .filter(|record| cutoff.is_none_or(|cutoff| record.shared_at >= cutoff))
+        .collect();
+    records.sort_by_key(|record| record.shared_at);
+
+    let mut out: Box<dyn Write> = match &args.out {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let now = Utc::now();
+    match args.format {
+        ExportFormat::Json => {
+            let entries: Vec<Entry> = records
+                .iter()
+                .map(|record| Entry {
+                    record,
+                    status: record.link_status(now).as_str(),
+                })
+                .collect();
+            serde_json::to_writer_pretty(&mut out, &entries)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer
+                .write_record([
+                    "shared_at",
+                    "item_id",
+                    "vault",
+                    "title",
+                    "project",
+                    "recipients",
+                    "expires_at",
+                    "status",
+                ])
+                .map_err(io::Error::from)?;
+            for record in &records {
+                writer
+                    .write_record([
+                        record.shared_at.to_rfc3339(),
+                        record.item_id.clone(),
+                        record.vault.clone(),
+                        record.title.clone(),
+                        record.project.clone().unwrap_or_default(),
+                        record.recipients.join(";"),
+                        record
+                            .expires_at
+                            .map(|expires_at| expires_at.to_rfc3339())
+                            .unwrap_or_default(),
+                        record.link_status(now).as_str().to_string(),
+                    ])
+                    .map_err(io::Error::from)?;
+            }
+            writer.flush()?;
+        }
+    }
+
+    if let Some(path) = &args.out {
+        info!("Exported {} record(s) to {}", records.len(), path.display());
+    }
+    Ok(())
+}
+
+/// Apply `--since`, `--grep`, and `--sort` to the records.
+fn filter(records: Vec<Record>, args: &HistoryArgs) -> Result<Vec<Record>> {
+    let cutoff = since(args.since.as_deref())?;
+    let mut records: Vec<Record> = records
+        .into_iter()
+        .filter(|record| cutoff.is_none_or(|cutoff| record.shared_at >= cutoff))
+        .filter(|record| {
+            args.grep
+                .as_deref()
+                .is_none_or(|needle| record.matches(needle))
+        })
+        .collect();
+
+    records.sort_by_key(|record| record.shared_at);
+    if args.sort == SortOrder::Newest {
+        records.reverse();
+    }
+    Ok(records)
+}
+
+/// The moment a `--since` duration points back to.
+pub fn since(since: Option<&str>) -> Result<Option<DateTime<Utc>>> {
+    since
+        .map(|since| {
+            expiry::parse(since)
+                .and_then(|duration| Utc::now().checked_sub_signed(duration))
+                .ok_or_else(|| ShareError::InvalidDuration {
+                    value: since.to_string(),
+                })
+        })
+        .transpose()
+}
+
+/// All records in the local history store.
+pub fn load() -> Result<Vec<Record>> {
+    match History::open() {
+        Some(history) => history.load(),
+        None => Ok(Vec::new()),
+    }
+}
@@ -0,0 +1,37 @@
+//! `share-1password list`: show the items this tool created in a vault.
+
+use super::Context;
+use crate::cli::ListArgs;
+use crate::table;
+use chrono::Utc;
+use share_1password::items;
+use share_1password::{info, Result};
+
+pub fn run(ctx: &Context, args: ListArgs) -> Result<()> {
+    let vault = ctx.vault(args.vault);
+
+    let backend = ctx.backend();
+    backend.ensure_signed_in()?;
+    let items = items::list(backend.as_ref(), &vault)?;
+
+    if items.is_empty() {
+        info!("No items created by share-1password in vault '{}'.", vault);
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            vec![
+                item.title.clone(),
+                table::format_time(item.created_at),
+                table::format_time(item.expires_at),
+                item.link_status(now).as_str().to_string(),
+            ]
+        })
+        .collect();
+    table::print(&["TITLE", "CREATED", "EXPIRES", "LINK"], &rows);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! `share-1password man`: generate man pages from the command line definition.
+
+use crate::cli::{Cli, ManArgs};
+use clap::CommandFactory;
+use clap_mangen::Man;
+use share_1password::{info, Result};
+use std::io::Write;
+use std::path::Path;
+
+pub fn run(args: ManArgs) -> Result<()> {
+    let mut command = Cli::command();
+    // Propagate global options to the subcommands, so their pages list them
+    command.build();
+
+    match args.out_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(&dir)?;
+            write_pages(&command, &dir)?;
+            info!("Man pages written to {}", dir.display());
+        }
+        None => {
+            let mut page = Vec::new();
+            Man::new(command).render(&mut page)?;
+            std::io::stdout().write_all(&page)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the page of `command` and, recursively, of its subcommands. Once built, subcommands are
+/// named after their full path, e.g. `share-1password-config-set`, and so are their pages.
+fn write_pages(command: &clap::Command, dir: &Path) -> Result<()> {
+    let man = Man::new(command.clone());
+    let mut page = Vec::new();
+    man.render(&mut page)?;
+    std::fs::write(dir.join(man.get_filename()), page)?;
+
+    for subcommand in command.get_subcommands() {
+        if subcommand.get_name() == "help" || subcommand.is_hide_set() {
+            continue;
+        }
+        write_pages(subcommand, dir)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+//! Handlers for the subcommands of the CLI.
+
+pub mod batch;
+pub mod completions;
+pub mod config;
+pub mod delete;
+pub mod doctor;
+pub mod extend;
+pub mod history;
+pub mod list;
+pub mod man;
+pub mod purge_expired;
+pub mod revoke;
+pub mod scan;
+pub mod self_update;
+pub mod share;
+pub mod stats;
+pub mod transfer;
+
+use crate::cli::{Backend, DeliveryArgs, OutputFormat};
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+use serde_json::json;
+use share::{Delivery, Shared};
+use share_1password::clipboard::Provider;
+use share_1password::history::{History, Record};
+use share_1password::op::OpBackend;
+use share_1password::output::Style;
+use share_1password::share::{DEFAULT_EXPIRES_IN, DEFAULT_VAULT};
+use share_1password::{expiry, output, recipients, warn, Config, Result, ShareError};
+use std::io::{self, IsTerminal};
+
+/// State shared by every subcommand.
+pub struct Context {
+    /// The effective configuration, with the selected profile and environment applied.
+    pub config: Config,
+    backend: Backend,
+}
+
+impl Context {
+    pub fn new(config: Config, backend: Backend) -> Self {
+        Self { config, backend }
+    }
+
+    /// The vault to act on: `vault` if given, else the configured one, else the default.
+    pub fn vault(&self, vault: Option<String>) -> String {
+        vault
+            .or_else(|| self.config.vault.clone())
+            .unwrap_or_else(|| DEFAULT_VAULT.to_string())
+    }
+
+    /// The link expiry to use: `expires_in` if given, else the configured one, else the default,
+    /// in the form `op` expects.
+    ///
+    /// An expiry longer than the configured `max_expires_in` is refused, or shortened to it if
+    /// `clamp_expires_in` is set.
+    pub fn expires_in(&self, expires_in: Option<String>) -> Result<String> {
+        let expires_in = expires_in
+            .or_else(|| self.config.expires_in.clone())
+            .unwrap_or_else(|| DEFAULT_EXPIRES_IN.to_string());
+        let expires_in = expiry::link_expiry(&expires_in)
+            .ok_or(ShareError::InvalidDuration { value: expires_in })?;
+
+        let Some(max) = &self.config.max_expires_in else {
+            return Ok(expires_in);
+        };
+        let max = expiry::link_expiry(max)
+            .ok_or_else(|| ShareError::InvalidDuration { value: max.clone() })?;
+        if expiry::parse(&expires_in) <= expiry::parse(&max) {
+            return Ok(expires_in);
+        }
+        if self.config.clamp_expires_in == Some(true) {
+            warn!(
+                "links may stay valid for at most {}, so the link expires in {} instead of {}.",
+                max, max, expires_in
+            );
+            return Ok(max);
+        }
+        Err(ShareError::Blocked {
+            reason: format!(
+                "links may stay valid for at most {}, not {} (max_expires_in)",
+                max, expires_in
+            ),
+        })
+    }
+
+    /// The recipients to use: `emails` if given, else the configured ones, split and deduplicated.
+    pub fn emails(&self, emails: Option<Vec<String>>) -> Vec<String> {
+        recipients::normalize(
+            &emails
+                .or_else(|| self.config.emails.clone())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Whether links stop working once viewed: if `view_once` is set, else as configured.
+    pub fn view_once(&self, view_once: bool) -> bool {
+        view_once || self.config.view_once == Some(true)
+    }
+
+    /// How to hand over finished shares: as given in `args`, else as configured.
+    ///
+    /// The output template is checked here, so that a typo fails before anything is shared.
+    pub fn delivery(&self, args: &DeliveryArgs) -> Result<Delivery> {
+        let template = args
+            .output_template
+            .clone()
+            .or_else(|| self.config.output_template.clone());
+        if let Some(template) = &template {
+            Shared::default().message(template)?;
+        }
+        let clipboard_timeout = match args
+            .clipboard_timeout
+            .clone()
+            .or_else(|| self.config.clipboard_timeout.clone())
+        {
+            Some(timeout) => Some(
+                expiry::parse(&timeout)
+                    .and_then(|timeout| timeout.to_std().ok())
+                    .ok_or(ShareError::InvalidDuration { value: timeout })?,
+            ),
+            None => None,
+        };
+        Ok(Delivery {
+            output: args.output,
+            link_format: args.link_format,
+            copy: args.copy,
+            template,
+            open: args.open || self.config.open == Some(true),
+            notify: args.notify,
+            clipboard: match &args.clipboard {
+                _ if args.no_clipboard || output::is_unattended() => None,
+                Some(provider) => Some(Provider::parse(provider)?),
+                None => Some(self.config.clipboard()?),
+            },
+            clipboard_timeout,
+        })
+    }
+
+    /// The email addresses to share with: [`emails`](Self::emails), with names of members of the
+    /// account looked up through `backend`, checked against the allowed email domains.
+    pub fn recipients(
+        &self,
+        emails: Option<Vec<String>>,
+        backend: &dyn OpBackend,
+    ) -> Result<Vec<String>> {
+        let emails = recipients::resolve(&self.emails(emails), || {
+            backend.ensure_signed_in()?;
+            backend.list_users()
+        })?;
+        self.check_domains(&emails)?;
+        Ok(emails)
+    }
+
+    /// Refuse to share with `emails` outside the configured `allowed_email_domains`.
+    pub fn check_domains(&self, emails: &[String]) -> Result<()> {
+        recipients::check_domains(
+            emails,
+            self.config
+                .allowed_email_domains
+                .as_deref()
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Build the selected 1Password backend for the configured account.
+    pub fn backend(&self) -> Box<dyn OpBackend> {
+        self.backend.build(self.config.account.clone())
+    }
+
+    /// Build the selected 1Password backend for `account`, or the configured one if not given.
+    pub fn backend_for(&self, account: Option<String>) -> Box<dyn OpBackend> {
+        self.backend
+            .build(account.or_else(|| self.config.account.clone()))
+    }
+}
+
+/// The look of questions asked on the terminal: without colors or symbols in plain mode.
+pub fn theme() -> Box<dyn Theme> {
+    if output::is_plain() {
+        Box::new(SimpleTheme)
+    } else {
+        Box::new(ColorfulTheme::default())
+    }
+}
+
+/// Whether there is a terminal to ask questions on.
+///
+/// Answers are read from the terminal even when stdin is piped, so only a process without one,
+/// e.g. in CI, cannot be asked. Neither is an [unattended](output::is_unattended) one.
+pub fn can_prompt() -> bool {
+    !output::is_unattended()
+        && io::stderr().is_terminal()
+        && (io::stdin().is_terminal() || std::fs::File::open("/dev/tty").is_ok())
+}
+
+/// Print `err` on stderr, as a JSON object with its exit code for `--output json`.
+pub fn print_error(err: &ShareError, output: OutputFormat) {
+    match output {
+        OutputFormat::Text => eprintln!("{}", Style::Error.stderr(err)),
+        OutputFormat::Json => eprintln!(
+            "{}",
+            json!({ "error": err.to_string(), "exit_code": err.exit_code() })
+        ),
+        OutputFormat::GithubActions => eprintln!("{}", output::github_error(&err.to_string())),
+    }
+}
+
+/// Report that sharing `name` failed with `err`, while other shares go on.
+pub fn print_failure(name: &str, err: &ShareError, output: OutputFormat) {
+    match output {
+        OutputFormat::Text => eprintln!(
+            "{}",
+            Style::Error.stderr(format!("Failed to share {}: {}", name, err))
+        ),
+        OutputFormat::Json => eprintln!(
+            "{}",
+            json!({ "name": name, "error": err.to_string(), "exit_code": err.exit_code() })
+        ),
+        OutputFormat::GithubActions => eprintln!(
+            "{}",
+            output::github_error(&format!("Failed to share {}: {}", name, err))
+        ),
+    }
+}
+
+/// Add `record` to the local history, warning instead of failing since the share itself succeeded.
+pub fn record_share(record: Record) {
+    let Some(history) = History::open() else {
+        return;
+    };
+    if let Err(err) = history.append(record) {
+        warn!(
+            "could not record the share in {}: {}",
+            history.path().display(),
+            err
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! `share-1password purge-expired`: remove items whose links are long dead.
+
+use super::{can_prompt, theme, Context};
+use crate::cli::PurgeExpiredArgs;
+use crate::table;
+use chrono::{Duration, Utc};
+use dialoguer::Confirm;
+use share_1password::op::DeleteMode;
+use share_1password::{expiry, info, items, output, warn, Result, ShareError};
+
+pub fn run(ctx: &Context, args: PurgeExpiredArgs) -> Result<()> {
+    let vault = ctx.vault(args.vault);
+    let grace = match args.grace.or_else(|| ctx.config.purge_grace.clone()) {
+        Some(grace) => expiry::parse(&grace).ok_or(ShareError::InvalidDuration { value: grace })?,
+        None => Duration::zero(),
+    };
+    let mode = if args.permanent {
+        DeleteMode::Permanent
+    } else {
+        DeleteMode::Archive
+    };
+
+    let backend = ctx.backend();
+    backend.ensure_signed_in()?;
+    let now = Utc::now();
+    let expired = items::expired(items::list(backend.as_ref(), &vault)?, now, grace);
+
+    if expired.is_empty() {
+        info!("No expired items to purge in vault '{}'.", vault);
+        return Ok(());
+    }
+
+    let action = match (args.dry_run, mode) {
+        (true, _) => "would remove",
+        (false, DeleteMode::Archive) => "archived",
+        (false, DeleteMode::Permanent) => "deleted",
+    };
+
+    if !args.dry_run && !args.yes && !output::is_unattended() {
+        if !can_prompt() {
+            return Err(ShareError::ConfirmationRequired {
+                action: format!("remove {} expired item(s)", expired.len()),
+            });
+        }
+        let confirmed = Confirm::with_theme(&*theme())
+            .with_prompt(format!(
+                "Remove {} expired item(s) from vault '{}'?",
+                expired.len(),
+                vault
+            ))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            info!("Nothing was removed.");
+            return Ok(());
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut failures = 0;
+    for item in &expired {
+        let status = if args.dry_run {
+            action.to_string()
+        } else {
+            match backend.delete_item(&item.id, &vault, mode) {
+                Ok(()) => action.to_string(),
+                Err(err) => {
+                    failures += 1;
+                    warn!("{}", err);
+                    "failed".to_string()
+                }
+            }
+        };
+        rows.push(vec![
+            item.title.clone(),
+            table::format_time(item.expires_at),
+            status,
+        ]);
+    }
+    table::print(&["TITLE", "EXPIRED", "RESULT"], &rows);
+
+    if failures > 0 {
+        warn!(
+            "{} of {} item(s) could not be removed.",
+            failures,
+            expired.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! `share-1password revoke`: kill the share links of an item.
+
+use super::Context;
+use crate::cli::RevokeArgs;
+use share_1password::history::History;
+use share_1password::{info, items, warn, Result};
+
+pub fn run(ctx: &Context, args: RevokeArgs) -> Result<()> {
+    let vault = ctx.vault(args.vault);
+
+    let backend = ctx.backend();
+    backend.ensure_signed_in()?;
+    let item = items::find(backend.as_ref(), &args.item, &vault)?;
+    items::revoke(backend.as_ref(), &item, &vault)?;
+
+    if let Some(history) = History::open() {
+        if let Err(err) = history.remove_item(&item.id) {
+            warn!(
+                "could not remove the item from {}: {}",
+                history.path().display(),
+                err
+            );
+        }
+    }
+
+    info!(
+        "Share links for '{}' no longer work. The item was moved to the archive.",
+        item.title
+    );
+    Ok(())
+}
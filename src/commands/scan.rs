@@ -0,0 +1,74 @@
+//! `share-1password scan`: find files holding secrets and share the chosen ones.
+
+use super::share::{enforce_policy, file_builder, files_title, report};
+use super::{can_prompt, theme, Context};
+use crate::cli::ScanArgs;
+use dialoguer::MultiSelect;
+use share_1password::git::RepoInfo;
+use share_1password::{info, output, scan, Result, ShareError};
+
+pub fn run(ctx: &Context, args: ScanArgs) -> Result<()> {
+    let root = match args.path {
+        Some(path) => path,
+        None => std::env::current_dir()?,
+    };
+    let files = scan::find(&root);
+    if files.is_empty() {
+        info!(
+            "No files matching {} found in {}.",
+            scan::PATTERNS.join(", "),
+            root.display()
+        );
+        return Ok(());
+    }
+
+    let names: Vec<String> = files
+        .iter()
+        .map(|path| {
+            path.strip_prefix(&root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        })
+        .collect();
+    let selected = if args.yes || output::is_unattended() {
+        (0..names.len()).collect()
+    } else if !can_prompt() {
+        return Err(ShareError::ConfirmationRequired {
+            action: format!("share the {} file(s) found", names.len()),
+        });
+    } else {
+        MultiSelect::with_theme(&*theme())
+            .with_prompt("Files to share, each as its own item")
+            .items(&names)
+            .interact()?
+    };
+    if selected.is_empty() {
+        info!("Nothing was shared.");
+        return Ok(());
+    }
+
+    let backend = ctx.backend();
+    let vault = ctx.vault(args.vault);
+    let expires_in = ctx.expires_in(args.expires_in)?;
+    let delivery = ctx.delivery(&args.delivery)?;
+    let recipients = ctx.recipients(args.emails, backend.as_ref())?;
+    let repo = (!args.no_git).then(|| RepoInfo::detect(&root)).flatten();
+
+    let mut results = Vec::new();
+    for index in selected {
+        let name = &names[index];
+        let result = file_builder(std::slice::from_ref(&files[index])).and_then(|builder| {
+            let builder = builder
+                .vault(vault.clone())
+                .expires_in(expires_in.clone())
+                .recipients(recipients.clone())
+                .title(files_title(&ctx.config, std::slice::from_ref(name))?)
+                .repo(repo.clone());
+            enforce_policy(&ctx.config, &builder, None)?;
+            builder.share_with(backend.as_ref())
+        });
+        results.push((name.clone(), result));
+    }
+    report(results, &delivery)
+}
@@ -0,0 +1,39 @@
+//! `share-1password self-update`: install the latest release over the running binary.
+//!
+//! The outcome is printed to stdout, so it shows with `--quiet` too.
+
+use crate::cli::SelfUpdateArgs;
+use share_1password::update::{self, CURRENT_VERSION, INSTALL_COMMAND, RELEASE_KEY};
+use share_1password::{info, Result};
+
+pub fn run(args: SelfUpdateArgs) -> Result<()> {
+    let release = update::latest()?;
+    if !release.is_newer() {
+        println!("share-1password {} is up to date.", CURRENT_VERSION);
+        return Ok(());
+    }
+    if args.check {
+        let command = match RELEASE_KEY {
+            Some(_) => "share-1password self-update",
+            None => INSTALL_COMMAND,
+        };
+        println!(
+            "share-1password {} is available, you have {}. Run `{}` to install it.",
+            release.version(),
+            CURRENT_VERSION,
+            command
+        );
+        return Ok(());
+    }
+
+    info!("Downloading share-1password {}...", release.version());
+    let binary = release.download()?;
+    let path = update::install(&binary)?;
+    println!(
+        "Updated {} from {} to {}.",
+        path.display(),
+        CURRENT_VERSION,
+        release.version()
+    );
+    Ok(())
+}
@@ -0,0 +1,1093 @@
+//! `share-1password share`: store stdin in 1Password and copy a share link.
+
+use super::{can_prompt, theme, Context};
+use crate::cli::{CopyTarget, LinkFormat, OutputFormat, PresetArg, SecretKindArg, ShareArgs};
+use crate::table;
+use chrono::{DateTime, Utc};
+use dialoguer::{Confirm, FuzzySelect, MultiSelect};
+use serde::Serialize;
+use share_1password::archive;
+use share_1password::clipboard::{self, Provider};
+use share_1password::codeowners::{self, Codeowners};
+use share_1password::compose::{self, ComposeFile};
+use share_1password::config::PublicLinks;
+use share_1password::dotenv;
+use share_1password::editor;
+use share_1password::filter::{self, KeyFilter};
+use share_1password::git::{self, RepoInfo};
+use share_1password::grant::Grant;
+use share_1password::history::Record;
+use share_1password::input::{self, InputFormat, Source};
+use share_1password::op::{DryRunBackend, OpBackend, ShareOptions};
+use share_1password::output::Style;
+use share_1password::policy::{Policy, Severity};
+use share_1password::preset;
+use share_1password::recent::RecentVaults;
+use share_1password::recipients;
+use share_1password::secrets::{self, SecretKind};
+use share_1password::sections::SectionRule;
+use share_1password::share::{self, DEFAULT_TITLE_FORMAT};
+use share_1password::{classify, info, output, template, title, warn};
+use share_1password::{Config, Result, ShareBuilder, ShareError, ShareResult, Summary};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub fn run(ctx: &Context, args: ShareArgs) -> Result<()> {
+    let config = &ctx.config;
+
+    // An existing item is shared as it is, unless only one of its fields is wanted
+    let item_field = match &args.from_item {
+        Some(item) => {
+            let vault = ctx.vault(args.from_vault.clone().or_else(|| args.vault.clone()));
+            match &args.field {
+                Some(field) => Some(item_field(ctx, item, &vault, field)?),
+                None => return share_item(ctx, item, &vault, &args),
+            }
+        }
+        None => None,
+    };
+    let (item_title, item_value) = match item_field {
+        Some((title, value)) => (args.title_format.is_none().then_some(title), Some(value)),
+        None => (None, None),
+    };
+    let delivery = ctx.delivery(&args.delivery)?;
+
+    let preset = args
+        .preset
+        .map(|preset| match preset {
+            PresetArg::Aws => preset::aws(args.aws_profile.as_deref()),
+            PresetArg::Kubeconfig => preset::kubeconfig(args.kube_context.as_deref()),
+            PresetArg::SshConfig => preset::ssh_config(),
+        })
+        .transpose()?;
+    // A title chosen on the command line wins over the one suggested by the preset
+    let preset_title = (args.title.is_none() && args.title_format.is_none())
+        .then(|| preset.as_ref().map(|preset| preset.title.clone()))
+        .flatten();
+
+    // Files shared without a chosen title are named in the default one
+    let names: Vec<String> = match &args.dir {
+        Some(dir) => vec![archive::file_name(dir, args.compress)],
+        None => args.files.iter().map(|path| file_name(path)).collect(),
+    };
+    let files_title = if args.title.is_none() && args.title_format.is_none() && !names.is_empty() {
+        Some(files_title(config, &names)?)
+    } else {
+        None
+    };
+
+    let (builder, input_format) = if let Some(preset) = &preset {
+        (ShareBuilder::new(preset.content.clone()), InputFormat::Raw)
+    } else if let Some(dir) = &args.dir {
+        let builder = ShareBuilder::from_binary(archive::tar(dir, args.compress)?)
+            .file_name(archive::file_name(dir, args.compress));
+        (builder, InputFormat::Raw)
+    } else if let Some(value) = item_value {
+        (ShareBuilder::new(value), args.input_format.into())
+    } else if args.edit {
+        (
+            ShareBuilder::new(editor::edit("")?),
+            args.input_format.into(),
+        )
+    } else if args.from_clipboard {
+        (
+            ShareBuilder::new(clipboard::paste()?),
+            args.input_format.into(),
+        )
+    } else if !args.files.is_empty() {
+        (file_builder(&args.files)?, args.input_format.into())
+    } else if args.compose_services.is_empty() {
+        // Read input from stdin, which may be binary, e.g. a keystore
+        let mut content = Vec::new();
+        io::stdin().read_to_end(&mut content)?;
+        let builder = ShareBuilder::from_bytes(content);
+        if builder.is_binary() {
+            info!("Binary input detected, sharing it as a document.");
+        }
+        (builder, args.input_format.into())
+    } else {
+        (
+            ShareBuilder::from_sources(compose_sources(
+                &args.compose_services,
+                args.compose_file.as_deref(),
+            )?),
+            InputFormat::Env,
+        )
+    };
+
+    let emails = given_emails(ctx, &args)?;
+    let backend = share_backend(ctx, args.dry_run);
+    let vault = match args.vault {
+        Some(vault) => vault,
+        None if args.pick_vault || config.pick_vault == Some(true) => {
+            pick_vault(ctx, backend.as_ref())?
+        }
+        None => ctx.vault(None),
+    };
+    let mut recipients = ctx.recipients(emails, backend.as_ref())?;
+    if args.pick_recipients {
+        recipients = pick_recipients(backend.as_ref(), recipients)?;
+        ctx.check_domains(&recipients)?;
+    }
+    let key_filter = KeyFilter {
+        only: args.only,
+        exclude: args.exclude,
+        pattern: args.key_regex.as_deref().map(filter::regex).transpose()?,
+    };
+    let mut builder = builder
+        .vault(vault)
+        .expires_in(ctx.expires_in(args.expires_in)?)
+        .recipients(recipients.clone())
+        .view_once(ctx.view_once(args.view_once))
+        .grants(
+            args.grants
+                .iter()
+                .map(|spec| Grant::parse(spec))
+                .collect::<Result<_>>()?,
+        )
+        .title_format(
+            args.title_format
+                .or_else(|| config.title_format.clone())
+                .unwrap_or_else(|| DEFAULT_TITLE_FORMAT.to_string()),
+        )
+        .category(
+            preset
+                .as_ref()
+                .map_or(args.category.into(), |preset| preset.category),
+        )
+        .input_format(input_format)
+        .structured(args.structured)
+        .normalize(args.normalize)
+        .strip_comments(args.strip_comments)
+        .redact(args.redact)
+        .sections(
+            args.sections
+                .iter()
+                .map(|spec| SectionRule::parse(spec))
+                .collect::<Result<_>>()?,
+        )
+        .key_filter(key_filter.clone())
+        .tags(
+            args.tags
+                .iter()
+                .map(|tag| tag.trim())
+                .filter(|tag| !tag.is_empty()),
+        );
+    if !args.no_git {
+        builder = builder.repo(RepoInfo::detect(&std::env::current_dir()?));
+    }
+    if let Some(notes) = args.notes.filter(|notes| !notes.trim().is_empty()) {
+        builder = builder.notes(notes);
+    }
+    if let Some(preset) = preset {
+        builder = builder.fields(preset.fields);
+        if let Some(file_name) = preset.file_name {
+            builder = builder.file_name(file_name);
+        }
+    }
+    if let Some(title) = args.title.or(preset_title).or(files_title).or(item_title) {
+        builder = builder.title(title);
+    }
+
+    if args.pick {
+        let Some(keys) = pick_keys(&builder)? else {
+            info!("Nothing was shared.");
+            return Ok(());
+        };
+        builder = builder.key_filter(KeyFilter {
+            only: keys,
+            ..key_filter
+        });
+    }
+
+    if args.normalize {
+        for key in builder.duplicate_keys()? {
+            warn!("{} is set more than once, keeping the last value.", key);
+        }
+    }
+    let dropped = builder.dropped_keys()?;
+    if !dropped.is_empty() {
+        info!("Leaving out {}.", dropped.join(", "));
+    }
+
+    let findings = check_secrets(&builder, &args.fail_on)?;
+    guard_public(config, &findings, &recipients, args.allow_public)?;
+    enforce_policy(config, &builder, args.policy_file.as_deref())?;
+
+    if args.preview {
+        match builder.preview() {
+            Some(preview) => eprint!("{}", preview),
+            None => {
+                info!("The content is not a set of variables, so there is nothing to preview.")
+            }
+        }
+    }
+
+    let summary = builder.summary(args.split)?;
+    if args.dry_run {
+        if !output::is_quiet() {
+            print_summary(&summary_rows(&summary));
+        }
+    } else if !args.yes && !output::is_unattended() && !confirm(&summary_rows(&summary))? {
+        info!("Nothing was shared.");
+        return Ok(());
+    }
+
+    if args.split {
+        let results = builder.share_split_with(backend.as_ref())?;
+        if args.dry_run {
+            return dry_run_done();
+        }
+        return report(results, &delivery);
+    }
+
+    // Keep the title that was confirmed, even if the template would render differently by now
+    let builder = builder.title(summary.title);
+    let result = builder.share_with(backend.as_ref())?;
+    if args.dry_run {
+        return dry_run_done();
+    }
+    deliver(&result, &delivery)
+}
+
+/// The recipients given on the command line: those in the --emails-file, the code owners with
+/// --emails-from-codeowners, and --emails.
+fn given_emails(ctx: &Context, args: &ShareArgs) -> Result<Option<Vec<String>>> {
+    if args.emails_file.is_none() && !args.emails_from_codeowners {
+        return Ok(args.emails.clone());
+    }
+    let mut emails = Vec::new();
+    if let Some(path) = &args.emails_file {
+        emails.extend(recipients::read_file(path)?);
+    }
+    if args.emails_from_codeowners {
+        emails.extend(codeowner_emails(&ctx.config, args)?);
+    }
+    emails.extend(args.emails.iter().flatten().cloned());
+    Ok(Some(emails))
+}
+
+/// The code owners of what is shared: the files or directory given, or else the current
+/// directory. Owners are mapped to email addresses by the `[codeowners]` config table; other
+/// users are looked up among the members of the account by their user name.
+fn codeowner_emails(config: &Config, args: &ShareArgs) -> Result<Vec<String>> {
+    let current_dir = std::env::current_dir()?;
+    let root = git::toplevel(&current_dir).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "--emails-from-codeowners only works in a git repository",
+        )
+    })?;
+    let codeowners = Codeowners::find(&root)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "No CODEOWNERS file found. Looked for {} in {}.",
+                codeowners::LOCATIONS.join(", "),
+                root.display()
+            ),
+        )
+    })?;
+
+    let paths = match &args.dir {
+        Some(dir) => vec![dir.clone()],
+        None if !args.files.is_empty() => args.files.clone(),
+        None => vec![current_dir.clone()],
+    };
+    let mut emails: Vec<String> = Vec::new();
+    for path in paths {
+        // git reports the root with symbolic links resolved
+        let path = current_dir.join(path);
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+        for owner in codeowners.owners(&path) {
+            let email = match config.codeowners.get(&owner) {
+                Some(email) => email.clone(),
+                None if classify::is_email(&owner) => owner,
+                None if owner.starts_with('@') && !owner.contains('/') => owner[1..].to_string(),
+                None => {
+                    warn!(
+                        "leaving out the team {}. Map it to an email address in the [codeowners] config table.",
+                        owner
+                    );
+                    continue;
+                }
+            };
+            if !emails.contains(&email) {
+                emails.push(email);
+            }
+        }
+    }
+    if emails.is_empty() {
+        warn!(
+            "{} names no owners for what is shared.",
+            codeowners.path().display()
+        );
+    }
+    Ok(emails)
+}
+
+/// The backend to share through, which only prints what it would do for a dry run.
+fn share_backend(ctx: &Context, dry_run: bool) -> Box<dyn OpBackend> {
+    let backend = ctx.backend();
+    if dry_run {
+        Box::new(DryRunBackend::new(backend, ctx.config.account.clone()))
+    } else {
+        backend
+    }
+}
+
+fn dry_run_done() -> Result<()> {
+    info!("Dry run: nothing was created or shared.");
+    Ok(())
+}
+
+/// The rows describing a share in the confirmation summary.
+fn summary_rows(summary: &Summary) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("Vault", summary.vault.clone()),
+        ("Title", summary.title.clone()),
+        ("Expires in", Style::Expiry.stderr(&summary.expires_in)),
+        ("Recipients", recipients_label(&summary.recipients)),
+    ];
+    if summary.view_once {
+        rows.push(("View once", "yes".to_string()));
+    }
+    if !summary.grants.is_empty() {
+        let groups: Vec<String> = summary
+            .grants
+            .iter()
+            .map(|grant| format!("{} ({})", grant.group, grant.permission.as_str()))
+            .collect();
+        rows.push(("Groups", groups.join(", ")));
+    }
+    if summary.keys > 0 {
+        rows.push(("Keys", summary.keys.to_string()));
+    }
+    if !summary.secrets.is_empty() {
+        rows.push(("Secrets", secrets::summary(&summary.secrets)));
+    }
+    rows
+}
+
+fn recipients_label(recipients: &[String]) -> String {
+    if recipients.is_empty() {
+        "ANYONE WITH LINK".to_string()
+    } else {
+        recipients.join(", ")
+    }
+}
+
+fn print_summary(rows: &[(&str, String)]) {
+    if output::is_plain() {
+        let rows: Vec<String> = rows
+            .iter()
+            .map(|(label, value)| format!("{}: {}", label, value))
+            .collect();
+        eprintln!("About to share: {}", rows.join("; "));
+        return;
+    }
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0) + 1;
+    eprintln!("About to share:");
+    for (label, value) in rows {
+        eprintln!(
+            "  {:<width$} {}",
+            format!("{}:", label),
+            value,
+            width = width
+        );
+    }
+}
+
+/// Show what is about to be shared and ask whether to go ahead.
+///
+/// Without a terminal to ask on, the summary is shown and the share goes ahead.
+fn confirm(rows: &[(&str, String)]) -> Result<bool> {
+    print_summary(rows);
+    if !can_prompt() {
+        return Ok(true);
+    }
+    Ok(Confirm::with_theme(&*theme())
+        .with_prompt("Share it?")
+        .default(false)
+        .interact()?)
+}
+
+/// Ask which vault to store the item in, starting on the one last picked in this project.
+///
+/// Without a terminal to ask on, the configured or default vault is used.
+fn pick_vault(ctx: &Context, backend: &dyn OpBackend) -> Result<String> {
+    if !can_prompt() {
+        return Ok(ctx.vault(None));
+    }
+    backend.ensure_signed_in()?;
+    let vaults: Vec<String> = backend
+        .list_vaults()?
+        .into_iter()
+        .map(|vault| vault.name)
+        .collect();
+    if vaults.is_empty() {
+        return Ok(ctx.vault(None));
+    }
+
+    let dir = std::env::current_dir()?;
+    let recent = RecentVaults::open();
+    let last = recent
+        .as_ref()
+        .and_then(|recent| recent.get(&dir))
+        .unwrap_or_else(|| ctx.vault(None));
+    let selection = FuzzySelect::with_theme(&*theme())
+        .with_prompt("Vault")
+        .items(&vaults)
+        .default(vaults.iter().position(|name| *name == last).unwrap_or(0))
+        .interact()?;
+    let vault = vaults[selection].clone();
+
+    if let Some(recent) = recent {
+        if let Err(err) = recent.set(&dir, &vault) {
+            warn!(
+                "could not remember the vault in {}: {}",
+                recent.path().display(),
+                err
+            );
+        }
+    }
+    Ok(vault)
+}
+
+/// Ask who to share the link with from the members of the account, in a single list to check
+/// any number of them in. The `selected` email addresses start out checked, and those of
+/// non-members are kept.
+fn pick_recipients(backend: &dyn OpBackend, selected: Vec<String>) -> Result<Vec<String>> {
+    backend.ensure_signed_in()?;
+    let users = backend.list_users()?;
+
+    let items: Vec<String> = users
+        .iter()
+        .map(|user| match user.name.as_str() {
+            "" => user.email.clone(),
+            name => format!("{} <{}>", name, user.email),
+        })
+        .collect();
+    let defaults: Vec<bool> = users
+        .iter()
+        .map(|user| selected.contains(&user.email))
+        .collect();
+    let checked = MultiSelect::with_theme(&*theme())
+        .with_prompt("Recipients")
+        .items(&items)
+        .defaults(&defaults)
+        .interact()?;
+
+    let mut chosen: Vec<String> = selected
+        .into_iter()
+        .filter(|email| !users.iter().any(|user| user.email == *email))
+        .collect();
+    chosen.extend(checked.into_iter().map(|index| users[index].email.clone()));
+    Ok(chosen)
+}
+
+/// Ask which of the variables to share, listing them with masked values. Returns the chosen keys,
+/// or `None` if none were chosen.
+fn pick_keys(builder: &ShareBuilder) -> Result<Option<Vec<String>>> {
+    let variables = builder.masked_variables().ok_or(ShareError::NoVariables)?;
+    let items: Vec<String> = variables
+        .iter()
+        .map(|(source, variable)| match source {
+            Some(source) => format!("{}={}  ({})", variable.key, variable.value, source),
+            None => format!("{}={}", variable.key, variable.value),
+        })
+        .collect();
+    let selected = MultiSelect::with_theme(&*theme())
+        .with_prompt("Variables to share")
+        .items(&items)
+        .defaults(&vec![true; items.len()])
+        .interact()?;
+
+    let mut keys: Vec<String> = Vec::new();
+    for index in selected {
+        let key = &variables[index].1.key;
+        if !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    Ok((!keys.is_empty()).then_some(keys))
+}
+
+/// Report the secrets recognized in the content, refusing to share any of the `fail_on` kinds.
+///
+/// Returns what was found.
+fn check_secrets(
+    builder: &ShareBuilder,
+    fail_on: &[SecretKindArg],
+) -> Result<Vec<(SecretKind, usize)>> {
+    let findings = builder.secrets();
+    if findings.is_empty() {
+        return Ok(findings);
+    }
+    warn!("The content contains {}.", secrets::summary(&findings));
+
+    let blocked: Vec<_> = findings
+        .iter()
+        .copied()
+        .filter(|(kind, _)| fail_on.iter().any(|&arg| SecretKind::from(arg) == *kind))
+        .collect();
+    if blocked.is_empty() {
+        return Ok(findings);
+    }
+    Err(ShareError::Blocked {
+        reason: format!(
+            "the content contains {}, which --fail-on forbids",
+            secrets::summary(&blocked)
+        ),
+    })
+}
+
+/// Refuse to create a link open to anyone when the configuration forbids it for the content,
+/// unless `allow_public` is set.
+fn guard_public(
+    config: &Config,
+    findings: &[(SecretKind, usize)],
+    recipients: &[String],
+    allow_public: bool,
+) -> Result<()> {
+    if allow_public || !recipients.is_empty() {
+        return Ok(());
+    }
+    let reason = match config.public_links()? {
+        PublicLinks::Allow => return Ok(()),
+        PublicLinks::Secrets if findings.is_empty() => return Ok(()),
+        PublicLinks::Secrets => format!(
+            "the content contains {} and the link would open for anyone who has it",
+            secrets::summary(findings)
+        ),
+        PublicLinks::Never => "links open to anyone are disabled by public_links".to_string(),
+    };
+    Err(ShareError::Blocked {
+        reason: format!(
+            "{}. Restrict it with --emails, or pass --allow-public",
+            reason
+        ),
+    })
+}
+
+/// Check the share against the policy at `policy_file`, or the configured one, warning about
+/// broken `warn` rules and refusing the share if it breaks a `deny` rule.
+pub(super) fn enforce_policy(
+    config: &Config,
+    builder: &ShareBuilder,
+    policy_file: Option<&Path>,
+) -> Result<()> {
+    let Some(path) = policy_file
+        .map(Path::to_path_buf)
+        .or_else(|| config.policy_file.as_ref().map(PathBuf::from))
+    else {
+        return Ok(());
+    };
+    let policy = Policy::load(&path)?;
+
+    let mut denied = Vec::new();
+    for violation in builder.check_policy(&policy) {
+        match violation.severity {
+            Severity::Warn => warn!("policy rule {}: {}.", violation.rule, violation.message),
+            Severity::Deny => denied.push(format!("{} ({})", violation.message, violation.rule)),
+        }
+    }
+    if denied.is_empty() {
+        return Ok(());
+    }
+    Err(ShareError::Blocked {
+        reason: format!("the policy forbids it: {}", denied.join("; ")),
+    })
+}
+
+/// A share as printed with `--output json`.
+#[derive(Default, Serialize)]
+pub(super) struct Shared<'a> {
+    pub share_url: &'a str,
+    pub item_id: &'a str,
+    pub vault: &'a str,
+    pub title: &'a str,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub recipients: &'a [String],
+}
+
+impl<'a> From<&'a ShareResult> for Shared<'a> {
+    fn from(result: &'a ShareResult) -> Self {
+        Self {
+            share_url: result.link.trim(),
+            item_id: &result.item_id,
+            vault: &result.vault,
+            title: &result.title,
+            expires_at: result.expires_at,
+            recipients: &result.recipients,
+        }
+    }
+}
+
+impl Shared<'_> {
+    /// Render an `--output-template`, filling in `{url}`, `{title}`, `{vault}`, `{item_id}`,
+    /// `{expires_at}`, and `{recipients}`.
+    pub(super) fn message(&self, template: &str) -> Result<String> {
+        let mut message = String::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            message.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let placeholder = &rest[start + 1..start + len];
+            rest = &rest[start + len + 1..];
+
+            match placeholder {
+                "url" => message.push_str(self.share_url),
+                "title" => message.push_str(self.title),
+                "vault" => message.push_str(self.vault),
+                "item_id" => message.push_str(self.item_id),
+                "expires_at" => message.push_str(&table::format_time(self.expires_at)),
+                "recipients" => message.push_str(&recipients_label(self.recipients)),
+                unknown => {
+                    return Err(ShareError::InvalidOutputTemplate {
+                        template: template.to_string(),
+                        placeholder: unknown.to_string(),
+                    })
+                }
+            }
+        }
+        message.push_str(rest);
+        Ok(message)
+    }
+
+    /// The link labeled with the item title, in `format`, followed by when it expires.
+    fn labeled(&self, format: LinkFormat) -> String {
+        let link = match format {
+            LinkFormat::Plain => self.share_url.to_string(),
+            LinkFormat::Markdown => format!(
+                "[{}]({})",
+                self.title.replace('[', "\\[").replace(']', "\\]"),
+                self.share_url
+            ),
+            LinkFormat::Html => format!(
+                "<a href=\"{}\">{}</a>",
+                html_escape(self.share_url),
+                html_escape(self.title)
+            ),
+            // Slack only needs &, <, and > escaped
+            LinkFormat::Slack => format!(
+                "<{}|{}>",
+                self.share_url,
+                self.title
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;")
+            ),
+        };
+        match self.expires_at {
+            Some(_) => format!("{} (expires {})", link, table::format_time(self.expires_at)),
+            None => link,
+        }
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// How the link of a finished share is handed over, from [`Context::delivery`].
+pub struct Delivery {
+    pub output: OutputFormat,
+    /// How to label the link, unless there is a template.
+    pub link_format: LinkFormat,
+    /// The `--output-template` to print and copy instead of the bare link.
+    pub template: Option<String>,
+    /// Whether to copy the link, the shared content, or both.
+    pub copy: CopyTarget,
+    /// Whether to open the link in the default browser.
+    pub open: bool,
+    /// Whether to show a desktop notification once the link is ready.
+    pub notify: bool,
+    /// Where to copy the link to, if anywhere.
+    pub clipboard: Option<Provider>,
+    /// How long the copied link is left on the clipboard.
+    pub clipboard_timeout: Option<Duration>,
+}
+
+impl Delivery {
+    /// What to print and copy for `shared` instead of the bare link: the message rendered from
+    /// the template, or the link labeled in the chosen format.
+    ///
+    /// A link format given on the command line wins over a configured template.
+    pub(super) fn message(&self, shared: &Shared) -> Result<Option<String>> {
+        match (self.link_format, &self.template) {
+            (LinkFormat::Plain, Some(template)) => shared.message(template).map(Some),
+            (LinkFormat::Plain, None) => Ok(None),
+            (format, _) => Ok(Some(shared.labeled(format))),
+        }
+    }
+
+    /// What to copy for shares printed as `printed`, given the content stored in each of them.
+    ///
+    /// An existing item that was shared as it is has no content at hand, so only its link is
+    /// copied.
+    fn clipboard_text(&self, printed: &str, contents: &[Option<&str>]) -> String {
+        if self.copy == CopyTarget::Link {
+            return printed.to_string();
+        }
+        let Some(contents) = contents.iter().copied().collect::<Option<Vec<&str>>>() else {
+            warn!("there is no shared content to copy, copying the link instead.");
+            return printed.to_string();
+        };
+        let content = contents
+            .iter()
+            .map(|content| content.trim_end_matches(['\r', '\n']))
+            .collect::<Vec<_>>()
+            .join("\n");
+        match self.copy {
+            CopyTarget::Both => format!("{}\n{}", printed.trim_end(), content),
+            _ => content,
+        }
+    }
+
+    /// The message telling what was copied, when what is printed is `printed`, e.g. "Link".
+    fn copied_label(&self, printed: &str) -> String {
+        match self.copy {
+            CopyTarget::Link => format!("{} copied to clipboard:", printed),
+            CopyTarget::Content => "Content copied to clipboard.".to_string(),
+            CopyTarget::Both => format!("{} and content copied to clipboard:", printed),
+        }
+    }
+
+    /// Place `text` on the clipboard, to be cleared once the timeout has passed, returning
+    /// whether it was copied.
+    ///
+    /// A clipboard that cannot be reached, e.g. on a headless server, only warns: the link has
+    /// been created and is printed either way.
+    pub(super) fn copy(&self, text: &str) -> bool {
+        let Some(provider) = &self.clipboard else {
+            return false;
+        };
+        if let Err(err) = provider.copy(text) {
+            warn!("{}. Pass --no-clipboard to only print the link.", err);
+            return false;
+        }
+        if let Some(timeout) = self.clipboard_timeout {
+            // What cannot be read back cannot be checked to be unchanged before clearing it
+            if !provider.can_clear() {
+                warn!(
+                    "the clipboard cannot be cleared after copying through {}.",
+                    provider.resolve()
+                );
+            } else if let Err(err) = clipboard::clear_after(provider, text, timeout) {
+                warn!("the clipboard will not be cleared: {}", err);
+            }
+        }
+        true
+    }
+
+    /// Open `link` in the browser if asked to, warning instead of failing since the share itself
+    /// succeeded.
+    pub(super) fn open(&self, link: &str) {
+        if !self.open {
+            return;
+        }
+        if let Err(err) = open::that_detached(link.trim()) {
+            warn!("could not open the link in a browser: {}", err);
+        }
+    }
+}
+
+/// Record the share and hand its link to the user, or the message made from it.
+pub(super) fn deliver(result: &ShareResult, delivery: &Delivery) -> Result<()> {
+    record(result);
+    let shared = Shared::from(result);
+    let message = delivery.message(&shared)?;
+
+    // Copy the link to the clipboard, or the message around it, or the content
+    let text = message.as_deref().unwrap_or(&result.link);
+    let copied = delivery.copy(&delivery.clipboard_text(text, &[result.content.as_deref()]));
+
+    match delivery.output {
+        OutputFormat::Text => {
+            if copied {
+                let printed = if message.is_some() { "Message" } else { "Link" };
+                info!("{}", Style::Success.stderr(delivery.copied_label(printed)));
+            }
+            match message {
+                Some(_) => println!("{}", text),
+                None => {
+                    let link = text.trim_end();
+                    println!("{}{}", Style::Link.stdout(link), &text[link.len()..])
+                }
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&shared)?),
+        OutputFormat::GithubActions => github_outputs(std::slice::from_ref(&shared))?,
+    }
+    delivery.open(&result.link);
+    if delivery.notify {
+        notify(&[shared]);
+    }
+    Ok(())
+}
+
+/// Show a desktop notification that the links of `shared` are ready, with their titles and
+/// expiry, warning instead of failing since the shares themselves succeeded.
+pub(super) fn notify(shared: &[Shared]) {
+    let (summary, body) = match shared {
+        [share] => (
+            "Share link ready".to_string(),
+            match share.expires_at {
+                Some(expires_at) => format!(
+                    "{}\nExpires {}",
+                    share.title,
+                    table::format_time(Some(expires_at))
+                ),
+                None => share.title.to_string(),
+            },
+        ),
+        _ => (
+            format!("{} share links ready", shared.len()),
+            shared
+                .iter()
+                .map(|share| share.title)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+    };
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("share-1password")
+        .summary(&summary)
+        .body(&body)
+        .show()
+    {
+        warn!("could not show a notification: {}", err);
+    }
+}
+
+/// Hand `shared` to later steps of a GitHub Actions job, masking the links in its log.
+///
+/// A single share is written as `share_url` and `item_id`, several as `share_urls` and
+/// `item_ids` with one per line.
+pub(super) fn github_outputs(shared: &[Shared]) -> Result<()> {
+    for share in shared {
+        println!("{}", output::github_mask(share.share_url));
+    }
+    let urls: Vec<&str> = shared.iter().map(|share| share.share_url).collect();
+    let ids: Vec<&str> = shared.iter().map(|share| share.item_id).collect();
+    let (urls_name, ids_name) = match shared {
+        [_] => ("share_url", "item_id"),
+        _ => ("share_urls", "item_ids"),
+    };
+    output::set_github_outputs(&[(urls_name, &urls.join("\n")), (ids_name, &ids.join("\n"))])
+}
+
+/// Share the link of the existing `item` in `vault`.
+fn share_item(ctx: &Context, item: &str, vault: &str, args: &ShareArgs) -> Result<()> {
+    let backend = share_backend(ctx, args.dry_run);
+    let options = ShareOptions {
+        expires_in: ctx.expires_in(args.expires_in.clone())?,
+        emails: ctx.recipients(given_emails(ctx, args)?, backend.as_ref())?,
+        view_once: ctx.view_once(args.view_once),
+    };
+    let delivery = ctx.delivery(&args.delivery)?;
+    guard_public(&ctx.config, &[], &options.emails, args.allow_public)?;
+    let mut rows = vec![
+        ("Vault", vault.to_string()),
+        ("Item", item.to_string()),
+        ("Expires in", Style::Expiry.stderr(&options.expires_in)),
+        ("Recipients", recipients_label(&options.emails)),
+    ];
+    if options.view_once {
+        rows.push(("View once", "yes".to_string()));
+    }
+    if args.dry_run {
+        if !output::is_quiet() {
+            print_summary(&rows);
+        }
+    } else if !args.yes && !output::is_unattended() && !confirm(&rows)? {
+        info!("Nothing was shared.");
+        return Ok(());
+    }
+    let result = share::share_existing(backend.as_ref(), item, vault, &options)?;
+    if args.dry_run {
+        return dry_run_done();
+    }
+    deliver(&result, &delivery)
+}
+
+/// The value of `field` of the existing `item` in `vault`, with a title naming both.
+fn item_field(ctx: &Context, item: &str, vault: &str, field: &str) -> Result<(String, String)> {
+    let backend = ctx.backend();
+    backend.ensure_signed_in()?;
+    let json = backend.get_item(item, vault)?;
+    let value =
+        template::find_field_value(&json, field).ok_or_else(|| ShareError::MissingField {
+            item: item.to_string(),
+            field: field.to_string(),
+        })?;
+    let title = json
+        .get("title")
+        .and_then(|title| title.as_str())
+        .unwrap_or(item);
+    Ok((format!("{} ({})", title, field), value.to_string()))
+}
+
+/// A share of the files at `paths`, each a source named after its file name.
+///
+/// A binary file is uploaded as a document under its own name, and cannot be combined with
+/// other files.
+pub(super) fn file_builder(paths: &[PathBuf]) -> Result<ShareBuilder> {
+    let mut sources = Vec::new();
+    for path in paths {
+        let content = std::fs::read(path).map_err(|err| ShareError::InputFile {
+            path: path.clone(),
+            message: err.to_string(),
+        })?;
+        match input::decode(content) {
+            Ok(content) => sources.push(Source {
+                name: Some(file_name(path)),
+                content,
+            }),
+            Err(_) if paths.len() > 1 => {
+                return Err(ShareError::InputFile {
+                    path: path.clone(),
+                    message: "binary files can only be shared on their own".to_string(),
+                })
+            }
+            Err(bytes) => {
+                info!("Binary file detected, sharing it as a document.");
+                return Ok(ShareBuilder::from_bytes(bytes).file_name(file_name(path)));
+            }
+        }
+    }
+    let builder = ShareBuilder::from_sources(sources);
+    Ok(match paths {
+        [path] => builder.file_name(file_name(path)),
+        _ => builder,
+    })
+}
+
+/// The title of a share of the files called `names`: the configured title template, followed by
+/// the names.
+pub(super) fn files_title(config: &Config, names: &[String]) -> Result<String> {
+    let format = config
+        .title_format
+        .as_deref()
+        .unwrap_or(DEFAULT_TITLE_FORMAT);
+    Ok(format!("{} ({})", title::render(format)?, names.join(", ")))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The environment of each compose service, as a `.env` file.
+fn compose_sources(services: &[String], compose_file: Option<&Path>) -> Result<Vec<Source>> {
+    let path = match compose_file {
+        Some(path) => path.to_path_buf(),
+        None => compose::find(&std::env::current_dir()?).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "No compose file found. Looked for {}; use --compose-file to name one.",
+                    compose::COMPOSE_FILES.join(", ")
+                ),
+            )
+        })?,
+    };
+    let compose_file = ComposeFile::load(&path)?;
+
+    services
+        .iter()
+        .map(|service| {
+            Ok(Source {
+                name: Some(service.clone()),
+                content: dotenv::format(&compose_file.environment(service)?),
+            })
+        })
+        .collect()
+}
+
+/// Record and print the links of several shares, copying them all to the clipboard. With a
+/// template, each share is printed as the message rendered from it instead.
+///
+/// Failed shares are reported as they come, and the first failure is returned.
+pub(super) fn report(
+    results: Vec<(String, Result<ShareResult>)>,
+    delivery: &Delivery,
+) -> Result<()> {
+    let mut summary = String::new();
+    let mut shared = Vec::new();
+    let mut first_error = None;
+    for (name, result) in results {
+        match result {
+            Ok(result) => {
+                record(&result);
+                let line = match delivery.message(&Shared::from(&result))? {
+                    Some(message) => message,
+                    None => format!("{}: {}", name, result.link.trim()),
+                };
+                summary.push_str(&line);
+                summary.push('\n');
+                shared.push(result);
+            }
+            Err(err) => {
+                super::print_failure(&name, &err, delivery.output);
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+
+    if !summary.is_empty() {
+        let contents: Vec<Option<&str>> = shared
+            .iter()
+            .map(|result| result.content.as_deref())
+            .collect();
+        let copied = delivery.copy(&delivery.clipboard_text(&summary, &contents));
+        match delivery.output {
+            OutputFormat::Text => {
+                if copied {
+                    info!("{}", Style::Success.stderr(delivery.copied_label("Links")));
+                }
+                print!("{}", summary);
+            }
+            OutputFormat::Json => {
+                let shared: Vec<Shared> = shared.iter().map(Shared::from).collect();
+                println!("{}", serde_json::to_string_pretty(&shared)?);
+            }
+            OutputFormat::GithubActions => {
+                github_outputs(&shared.iter().map(Shared::from).collect::<Vec<_>>())?
+            }
+        }
+        for result in &shared {
+            delivery.open(&result.link);
+        }
+        if delivery.notify {
+            notify(&shared.iter().map(Shared::from).collect::<Vec<_>>());
+        }
+    }
+
+    match first_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+pub(super) fn record(result: &ShareResult) {
+    super::record_share(Record {
+        item_id: result.item_id.clone(),
+        vault: result.vault.clone(),
+        title: result.title.clone(),
+        recipients: result.recipients.clone(),
+        expires_at: result.expires_at,
+        shared_at: Utc::now(),
+        project: title::project_name().ok(),
+    });
+}
@@ -0,0 +1,47 @@
+//! `share-1password stats`: summarize sharing activity from the local history.
+
+use super::history;
+use crate::cli::StatsArgs;
+use crate::table;
+use share_1password::history::Stats;
+use share_1password::Result;
+
+pub fn run(args: StatsArgs) -> Result<()> {
+    let cutoff = history::since(args.since.as_deref())?;
+    let records: Vec<_> = history::load()?
+        .into_iter()
+        .filter(|record| cutoff.is_none_or(|cutoff| record.shared_at >= cutoff))
+        .collect();
+    let stats = Stats::from_records(&records);
+
+    match &args.since {
+        Some(since) => println!("Sharing activity in the last {}:", since),
+        None => println!("Sharing activity:"),
+    }
+    table::print(
+        &["METRIC", "VALUE"],
+        &[
+            vec!["Shares".to_string(), stats.shares.to_string()],
+            vec![
+                "Unique recipients".to_string(),
+                stats.unique_recipients.to_string(),
+            ],
+            vec![
+                "Shares without recipient restriction".to_string(),
+                stats.unrestricted.to_string(),
+            ],
+        ],
+    );
+
+    if !stats.projects.is_empty() {
+        println!();
+        let rows: Vec<Vec<String>> = stats
+            .projects
+            .iter()
+            .take(args.top)
+            .map(|(project, count)| vec![project.clone(), count.to_string()])
+            .collect();
+        table::print(&["PROJECT", "SHARES"], &rows);
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+//! `share-1password transfer`: copy an item to another vault or account.
+
+use super::share::deliver;
+use super::Context;
+use crate::cli::TransferArgs;
+use chrono::SecondsFormat;
+use share_1password::items::{EXPIRES_AT_FIELD, TOOL_TAG};
+use share_1password::op::{DeleteMode, ShareOptions};
+use share_1password::{info, share, transfer, Result};
+
+pub fn run(ctx: &Context, args: TransferArgs) -> Result<()> {
+    let vault = ctx.vault(args.vault);
+    let delivery = ctx.delivery(&args.delivery)?;
+    let from = ctx.backend();
+    let to = ctx.backend_for(args.to_account.clone());
+
+    // A shared copy is tracked like any other item shared by this tool
+    let tags: &[&str] = if args.share { &[TOOL_TAG] } else { &[] };
+    let (item_id, title) = transfer::copy_item(
+        from.as_ref(),
+        &args.item,
+        &vault,
+        to.as_ref(),
+        &args.to_vault,
+        tags,
+    )?;
+    info!("Copied '{}' to vault '{}'.", title, args.to_vault);
+
+    let shared = if args.share {
+        let options = ShareOptions {
+            expires_in: ctx.expires_in(args.expires_in)?,
+            emails: ctx.recipients(args.emails, to.as_ref())?,
+            view_once: ctx.view_once(args.view_once),
+        };
+        let result = share::share_existing(to.as_ref(), &item_id, &args.to_vault, &options)?;
+        if let Some(expires_at) = result.expires_at {
+            to.set_item_field(
+                &item_id,
+                &args.to_vault,
+                EXPIRES_AT_FIELD,
+                &expires_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            )?;
+        }
+        Some(result)
+    } else {
+        None
+    };
+
+    if args.move_item {
+        from.delete_item(&args.item, &vault, DeleteMode::Archive)?;
+        info!("Moved the original to the archive of vault '{}'.", vault);
+    }
+    match shared {
+        Some(result) => deliver(&result, &delivery),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,88 @@
+//! Completion of vault names from 1Password and item titles from the local history, for the
+//! completion scripts registered through `COMPLETE=<shell> share-1password`.
+
+use crate::commands::history;
+use clap_complete::engine::CompletionCandidate;
+use serde::{Deserialize, Serialize};
+use share_1password::op::{CliBackend, OpBackend};
+use share_1password::{config, Config};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// How long listed vaults are reused, so repeated tab presses do not each wait for `op`.
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Name of the vault cache in [`config::cache_dir`].
+const VAULTS_CACHE_FILE: &str = "vaults.json";
+
+#[derive(Serialize, Deserialize)]
+struct VaultCache {
+    account: Option<String>,
+    vaults: Vec<String>,
+}
+
+/// The names of the vaults in the configured account.
+pub fn vaults() -> Vec<CompletionCandidate> {
+    vault_names()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// The titles of shared items, most recently shared first, with their vault as help.
+pub fn items() -> Vec<CompletionCandidate> {
+    let mut seen = HashSet::new();
+    history::load()
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .filter(|record| seen.insert(record.title.clone()))
+        .map(|record| CompletionCandidate::new(record.title).help(Some(record.vault.into())))
+        .collect()
+}
+
+/// Vault names from the cache if it is recent and for the same account, else from `op`.
+fn vault_names() -> Vec<String> {
+    let account = Config::load()
+        .map(|config| config.merge(Config::from_env()).account)
+        .unwrap_or_default();
+    let path = config::cache_dir().map(|dir| dir.join(VAULTS_CACHE_FILE));
+
+    let fresh = path.as_ref().is_some_and(|path| {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < CACHE_TTL)
+    });
+    if fresh {
+        let cache = path
+            .as_ref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|contents| serde_json::from_slice::<VaultCache>(&contents).ok());
+        if let Some(cache) = cache.filter(|cache| cache.account == account) {
+            return cache.vaults;
+        }
+    }
+
+    // Never sign in: there is no way to answer prompts in the middle of a completion
+    let Ok(vaults) = CliBackend::new()
+        .with_account(account.clone())
+        .list_vaults()
+    else {
+        return Vec::new();
+    };
+    let vaults: Vec<String> = vaults.into_iter().map(|vault| vault.name).collect();
+
+    // A cache that cannot be written only makes the next completion slower
+    if let Some(path) = path {
+        let cache = VaultCache {
+            account,
+            vaults: vaults.clone(),
+        };
+        if let (Some(dir), Ok(contents)) = (path.parent(), serde_json::to_vec(&cache)) {
+            let _ = std::fs::create_dir_all(dir).and_then(|()| std::fs::write(&path, contents));
+        }
+    }
+    vaults
+}
@@ -0,0 +1,162 @@
+//! Reading the environment of docker-compose services.
+
+use crate::dotenv::{self, Variable};
+use crate::error::{Result, ShareError};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Names docker compose looks for in the project directory, in order of preference.
+pub const COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// Find the compose file in `dir`.
+pub fn find(dir: &Path) -> Option<PathBuf> {
+    COMPOSE_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// A parsed compose file.
+#[derive(Debug, Clone)]
+pub struct ComposeFile {
+    path: PathBuf,
+    document: Value,
+}
+
+impl ComposeFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let document = serde_yaml::from_str(&contents).map_err(|err| ShareError::Compose {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            document,
+        })
+    }
+
+    /// The environment of `service`: its `env_file` entries in order, overridden by its
+    /// `environment` block.
+    ///
+    /// `env_file` paths are relative to the compose file. Variables listed without a value are
+    /// taken from the current environment, and left out if it does not set them.
+    pub fn environment(&self, service: &str) -> Result<Vec<Variable>> {
+        let services = self.document.get("services").and_then(Value::as_object);
+        let Some(definition) = services.and_then(|services| services.get(service)) else {
+            let available: Vec<&str> = services
+                .map(|services| services.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            return Err(self.error(format!(
+                "no service '{}'. Available services: {}",
+                service,
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                }
+            )));
+        };
+
+        let mut variables = Vec::new();
+        for (env_file, required) in self.env_files(definition) {
+            let path = self.path.parent().unwrap_or(Path::new(".")).join(&env_file);
+            let contents = match std::fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound && !required => continue,
+                Err(err) => {
+                    return Err(self.error(format!("cannot read env_file {}: {}", env_file, err)))
+                }
+            };
+            let parsed = dotenv::parse(&contents)
+                .map_err(|err| self.error(format!("in env_file {}: {}", env_file, err)))?;
+            for variable in parsed {
+                set(&mut variables, variable);
+            }
+        }
+
+        match definition.get("environment") {
+            Some(Value::Object(entries)) => {
+                for (key, value) in entries {
+                    let value = match value {
+                        Value::Null => std::env::var(key).ok(),
+                        Value::String(value) => Some(value.clone()),
+                        other => Some(other.to_string()),
+                    };
+                    if let Some(value) = value {
+                        set(
+                            &mut variables,
+                            Variable {
+                                key: key.clone(),
+                                value,
+                            },
+                        );
+                    }
+                }
+            }
+            Some(Value::Array(entries)) => {
+                for entry in entries.iter().filter_map(Value::as_str) {
+                    let variable = match entry.split_once('=') {
+                        Some((key, value)) => Some(Variable {
+                            key: key.to_string(),
+                            value: value.to_string(),
+                        }),
+                        None => std::env::var(entry).ok().map(|value| Variable {
+                            key: entry.to_string(),
+                            value,
+                        }),
+                    };
+                    if let Some(variable) = variable {
+                        set(&mut variables, variable);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(variables)
+    }
+
+    /// The `env_file` paths of a service and whether they must exist, in any of the forms
+    /// compose accepts.
+    fn env_files(&self, definition: &Value) -> Vec<(String, bool)> {
+        let entry_path = |entry: &Value| match entry {
+            Value::String(path) => Some((path.clone(), true)),
+            Value::Object(entry) => Some((
+                entry.get("path")?.as_str()?.to_string(),
+                entry
+                    .get("required")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true),
+            )),
+            _ => None,
+        };
+        match definition.get("env_file") {
+            Some(Value::Array(entries)) => entries.iter().filter_map(entry_path).collect(),
+            Some(entry) => entry_path(entry).into_iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn error(&self, message: String) -> ShareError {
+        ShareError::Compose {
+            path: self.path.clone(),
+            message,
+        }
+    }
+}
+
+/// Set a variable, replacing the value of an earlier one with the same key in place.
+fn set(variables: &mut Vec<Variable>, variable: Variable) {
+    match variables
+        .iter_mut()
+        .find(|existing| existing.key == variable.key)
+    {
+        Some(existing) => existing.value = variable.value,
+        None => variables.push(variable),
+    }
+}
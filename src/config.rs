@@ -0,0 +1,538 @@
+//! User configuration, read from `~/.config/share-1password/config.toml` and a per-project
+//! `.share1password.toml`.
+//!
+//! Every setting is optional. The project file overrides the global file, `SHARE_1PASSWORD_*`
+//! environment variables override both, and values given on the command line take precedence over
+//! everything. Named `[profiles.<name>]` sections hold alternative sets of settings that override
+//! the top-level ones when selected.
+
+use crate::clipboard::Provider;
+use crate::error::{Result, ShareError};
+use crate::expiry;
+use crate::recipients;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// File name of the global configuration file.
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// File name of the per-project configuration file.
+pub const PROJECT_CONFIG_FILE: &str = ".share1password.toml";
+
+/// Keys a project file may not set, because a checked-out repository could use them to get
+/// around the rules and limits set up elsewhere. Project files setting them are ignored.
+pub const TRUSTED_KEYS: &[&str] = &[
+    "public_links",
+    "allowed_email_domains",
+    "policy_file",
+    "max_expires_in",
+    "clamp_expires_in",
+    "codeowners",
+];
+
+/// Prefix of the environment variables that configure this tool.
+pub const ENV_PREFIX: &str = "SHARE_1PASSWORD_";
+
+/// Keys accepted by [`Config::get`] and [`Config::set`].
+pub const KEYS: &[&str] = &[
+    "account",
+    "vault",
+    "expires_in",
+    "emails",
+    "title_format",
+    "purge_grace",
+    "policy_file",
+    "public_links",
+    "pick_vault",
+    "allowed_email_domains",
+    "view_once",
+    "max_expires_in",
+    "clamp_expires_in",
+    "output_template",
+    "open",
+    "clipboard_timeout",
+    "clipboard",
+    "plain",
+];
+
+/// When share links open to anyone with the link may be created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PublicLinks {
+    /// Unless secrets are detected in the content.
+    #[default]
+    Secrets,
+    /// Never: every link needs recipients.
+    Never,
+    /// Always.
+    Allow,
+}
+
+impl PublicLinks {
+    pub const VALUES: &'static [&'static str] = &["secrets", "never", "allow"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "secrets" => Ok(PublicLinks::Secrets),
+            "never" => Ok(PublicLinks::Never),
+            "allow" => Ok(PublicLinks::Allow),
+            _ => Err(ShareError::InvalidConfigValue {
+                key: "public_links".to_string(),
+                value: value.to_string(),
+                expected: Self::VALUES.join(", "),
+            }),
+        }
+    }
+}
+
+/// Defaults for the subcommands.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The 1Password account to use, as a shorthand, sign-in address, or account ID.
+    pub account: Option<String>,
+    /// The vault to store items in.
+    pub vault: Option<String>,
+    /// Expiration time for share links.
+    pub expires_in: Option<String>,
+    /// Email addresses to share items with.
+    pub emails: Option<Vec<String>>,
+    /// Template for item titles, e.g. `"[{dir}] - {date}"`. See [`crate::title`].
+    pub title_format: Option<String>,
+    /// How long after its link expired an item is kept before `purge-expired` removes it.
+    pub purge_grace: Option<String>,
+    /// The policy file shares are checked against. See [`crate::policy`].
+    pub policy_file: Option<String>,
+    /// When links open to anyone may be created: `secrets` (unless secrets are detected, the
+    /// default), `never`, or `allow`.
+    pub public_links: Option<String>,
+    /// Whether to pick the vault from a list when `--vault` is not given.
+    pub pick_vault: Option<bool>,
+    /// The only email domains links may be shared with, including their subdomains.
+    pub allowed_email_domains: Option<Vec<String>>,
+    /// Whether share links stop working once they have been viewed.
+    pub view_once: Option<bool>,
+    /// The longest a share link may stay valid, e.g. `7d`.
+    pub max_expires_in: Option<String>,
+    /// Whether a longer expiry than `max_expires_in` is shortened to it instead of refused.
+    pub clamp_expires_in: Option<bool>,
+    /// Message printed and copied instead of the bare link, e.g. `"{title}: {url}"`.
+    pub output_template: Option<String>,
+    /// Whether to open share links in the default browser once they are ready.
+    pub open: Option<bool>,
+    /// How long a copied link stays on the clipboard before it is cleared, e.g. `90s`.
+    pub clipboard_timeout: Option<String>,
+    /// Where links are copied to: `auto` (the default), `arboard`, `osc52`, `wsl`, `wl-copy`,
+    /// `xclip`, `pbcopy`, or `command:<cmd>`. See [`crate::clipboard::Provider`].
+    pub clipboard: Option<String>,
+    /// Whether to print without colors, symbols, or spinners, as with `--plain`.
+    pub plain: Option<bool>,
+    /// Email addresses of `CODEOWNERS` owners, such as `"@anna"` or `"@acme/devops"`, for
+    /// `--emails-from-codeowners`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub codeowners: BTreeMap<String, String>,
+    /// Named sets of settings, selected with `--profile`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Config>,
+}
+
+impl Config {
+    /// Load the global configuration, overridden by the project configuration found from the
+    /// current directory.
+    pub fn load() -> Result<Self> {
+        let global = match global_path() {
+            Some(path) => Self::load_from(&path)?,
+            None => Self::default(),
+        };
+        let project = match project_path(&std::env::current_dir()?) {
+            Some(path) => {
+                let (project, ignored) = Self::load_from(&path)?.trusted();
+                if !ignored.is_empty() {
+                    crate::warn!(
+                        "ignoring {} in {}: set them in the global configuration or on the command line.",
+                        ignored.join(", "),
+                        path.display()
+                    );
+                }
+                project
+            }
+            None => Self::default(),
+        };
+        Ok(global.merge(project))
+    }
+
+    /// The configuration without the settings a project file may not make, and the keys left
+    /// out: any of [`TRUSTED_KEYS`] and a `command:` clipboard provider, in the top level or a
+    /// profile.
+    pub fn trusted(mut self) -> (Config, Vec<String>) {
+        let mut ignored = Vec::new();
+        self.remove_untrusted("", &mut ignored);
+        for (name, profile) in &mut self.profiles {
+            profile.remove_untrusted(&format!("profiles.{}.", name), &mut ignored);
+        }
+        (self, ignored)
+    }
+
+    fn remove_untrusted(&mut self, prefix: &str, ignored: &mut Vec<String>) {
+        let mut ignore = |set: bool, key: &str| {
+            if set {
+                ignored.push(format!("{}{}", prefix, key));
+            }
+        };
+        ignore(self.public_links.take().is_some(), "public_links");
+        ignore(
+            self.allowed_email_domains.take().is_some(),
+            "allowed_email_domains",
+        );
+        ignore(self.policy_file.take().is_some(), "policy_file");
+        ignore(self.max_expires_in.take().is_some(), "max_expires_in");
+        ignore(self.clamp_expires_in.take().is_some(), "clamp_expires_in");
+        ignore(
+            !std::mem::take(&mut self.codeowners).is_empty(),
+            "codeowners",
+        );
+        if let Some(Ok(Provider::Command(_))) = self.clipboard.as_deref().map(Provider::parse) {
+            self.clipboard = None;
+            ignore(true, "clipboard");
+        }
+    }
+
+    /// Load configuration from `path`, or the empty configuration if it does not exist.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        toml::from_str(&contents).map_err(|err| ShareError::Config {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })
+    }
+
+    /// Write the configuration to `path`, creating parent directories as needed.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = toml::to_string_pretty(self).map_err(|err| ShareError::Config {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// The value of `key` formatted for display, or `None` if it is unset.
+    ///
+    /// Lists are joined with commas.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match key {
+            "account" => self.account.clone(),
+            "vault" => self.vault.clone(),
+            "expires_in" => self.expires_in.clone(),
+            "emails" => self.emails.as_ref().map(|emails| emails.join(",")),
+            "title_format" => self.title_format.clone(),
+            "purge_grace" => self.purge_grace.clone(),
+            "policy_file" => self.policy_file.clone(),
+            "public_links" => self.public_links.clone(),
+            "pick_vault" => self.pick_vault.map(|pick| pick.to_string()),
+            "view_once" => self.view_once.map(|view_once| view_once.to_string()),
+            "max_expires_in" => self.max_expires_in.clone(),
+            "clamp_expires_in" => self.clamp_expires_in.map(|clamp| clamp.to_string()),
+            "output_template" => self.output_template.clone(),
+            "open" => self.open.map(|open| open.to_string()),
+            "clipboard_timeout" => self.clipboard_timeout.clone(),
+            "clipboard" => self.clipboard.clone(),
+            "plain" => self.plain.map(|plain| plain.to_string()),
+            "allowed_email_domains" => self
+                .allowed_email_domains
+                .as_ref()
+                .map(|domains| domains.join(",")),
+            _ => return Err(unknown_key(key)),
+        })
+    }
+
+    /// Set `key` from its display form. An empty value unsets the key.
+    ///
+    /// Lists are split on commas and whitespace.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let value = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+        match key {
+            "account" => self.account = value,
+            "vault" => self.vault = value,
+            "expires_in" => self.expires_in = value.map(parse_link_expiry).transpose()?,
+            "emails" => self.emails = value.map(|value| recipients::split(&value)),
+            "title_format" => self.title_format = value,
+            "purge_grace" => self.purge_grace = value,
+            "policy_file" => self.policy_file = value,
+            "public_links" => {
+                if let Some(value) = &value {
+                    PublicLinks::parse(value)?;
+                }
+                self.public_links = value
+            }
+            "allowed_email_domains" => {
+                self.allowed_email_domains = value.map(|value| split_list(&value))
+            }
+            "pick_vault" => {
+                self.pick_vault = value.map(|value| parse_bool(key, &value)).transpose()?
+            }
+            "view_once" => {
+                self.view_once = value.map(|value| parse_bool(key, &value)).transpose()?
+            }
+            "max_expires_in" => self.max_expires_in = value.map(parse_link_expiry).transpose()?,
+            "clamp_expires_in" => {
+                self.clamp_expires_in = value.map(|value| parse_bool(key, &value)).transpose()?
+            }
+            "output_template" => self.output_template = value,
+            "open" => self.open = value.map(|value| parse_bool(key, &value)).transpose()?,
+            "clipboard_timeout" => {
+                self.clipboard_timeout = value.map(parse_duration).transpose()?
+            }
+            "clipboard" => {
+                if let Some(value) = &value {
+                    Provider::parse(value)?;
+                }
+                self.clipboard = value
+            }
+            "plain" => self.plain = value.map(|value| parse_bool(key, &value)).transpose()?,
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    /// The configured [`PublicLinks`] setting.
+    pub fn public_links(&self) -> Result<PublicLinks> {
+        self.public_links
+            .as_deref()
+            .map_or(Ok(PublicLinks::default()), PublicLinks::parse)
+    }
+
+    /// The configured clipboard [`Provider`].
+    pub fn clipboard(&self) -> Result<Provider> {
+        self.clipboard
+            .as_deref()
+            .map_or(Ok(Provider::default()), Provider::parse)
+    }
+
+    /// Settings given through `SHARE_1PASSWORD_*` environment variables that have no command line
+    /// flag. Variables for flagged settings, such as `SHARE_1PASSWORD_VAULT`, are read by the CLI.
+    pub fn from_env() -> Config {
+        let var = |name: &str| {
+            std::env::var(format!("{}{}", ENV_PREFIX, name))
+                .ok()
+                .filter(|value| !value.is_empty())
+        };
+        Config {
+            account: var("ACCOUNT"),
+            ..Config::default()
+        }
+    }
+
+    /// Combine two configurations, with values set in `overrides` taking precedence.
+    ///
+    /// Profiles are merged by name, so a project file can adjust a single setting of a global profile.
+    pub fn merge(self, overrides: Config) -> Config {
+        let mut codeowners = self.codeowners;
+        codeowners.extend(overrides.codeowners);
+        let mut profiles = self.profiles;
+        for (name, profile) in overrides.profiles {
+            let merged = match profiles.remove(&name) {
+                Some(base) => base.merge(profile),
+                None => profile,
+            };
+            profiles.insert(name, merged);
+        }
+
+        Config {
+            account: overrides.account.or(self.account),
+            vault: overrides.vault.or(self.vault),
+            expires_in: overrides.expires_in.or(self.expires_in),
+            emails: overrides.emails.or(self.emails),
+            title_format: overrides.title_format.or(self.title_format),
+            purge_grace: overrides.purge_grace.or(self.purge_grace),
+            policy_file: overrides.policy_file.or(self.policy_file),
+            public_links: overrides.public_links.or(self.public_links),
+            pick_vault: overrides.pick_vault.or(self.pick_vault),
+            view_once: overrides.view_once.or(self.view_once),
+            max_expires_in: overrides.max_expires_in.or(self.max_expires_in),
+            clamp_expires_in: overrides.clamp_expires_in.or(self.clamp_expires_in),
+            output_template: overrides.output_template.or(self.output_template),
+            open: overrides.open.or(self.open),
+            clipboard_timeout: overrides.clipboard_timeout.or(self.clipboard_timeout),
+            clipboard: overrides.clipboard.or(self.clipboard),
+            plain: overrides.plain.or(self.plain),
+            allowed_email_domains: overrides
+                .allowed_email_domains
+                .or(self.allowed_email_domains),
+            codeowners,
+            profiles,
+        }
+    }
+
+    /// The settings in effect with `profile` selected, if any.
+    pub fn with_profile(mut self, profile: Option<&str>) -> Result<Config> {
+        let Some(name) = profile else {
+            return Ok(self);
+        };
+        let Some(mut selected) = self.profiles.remove(name) else {
+            return Err(ShareError::UnknownProfile {
+                profile: name.to_string(),
+                available: if self.profiles.is_empty() {
+                    "none".to_string()
+                } else {
+                    self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+                },
+            });
+        };
+        // Profiles do not nest
+        selected.profiles.clear();
+        Ok(self.merge(selected))
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    value.parse().map_err(|_| ShareError::InvalidConfigValue {
+        key: key.to_string(),
+        value: value.to_string(),
+        expected: "true, false".to_string(),
+    })
+}
+
+/// A duration in the form `op` expects.
+fn parse_duration(value: String) -> Result<String> {
+    expiry::normalize(&value).ok_or(ShareError::InvalidDuration { value })
+}
+
+/// A share link expiry in the form `op` expects, no longer than it allows.
+fn parse_link_expiry(value: String) -> Result<String> {
+    expiry::link_expiry(&value).ok_or(ShareError::InvalidDuration { value })
+}
+
+fn unknown_key(key: &str) -> ShareError {
+    ShareError::UnknownConfigKey {
+        key: key.to_string(),
+    }
+}
+
+/// Split a comma- or whitespace-separated list, dropping empty entries.
+pub fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The directory holding this tool's configuration: `$XDG_CONFIG_HOME/share-1password`,
+/// falling back to `~/.config/share-1password`.
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".config")))
+        .map(|dir| dir.join("share-1password"))
+}
+
+/// The directory for data that can be thrown away: `$XDG_CACHE_HOME/share-1password`, falling
+/// back to `~/.cache/share-1password`.
+pub fn cache_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".cache")))
+        .map(|dir| dir.join("share-1password"))
+}
+
+/// Path of the global configuration file.
+pub fn global_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CONFIG_FILE))
+}
+
+/// Find the nearest `.share1password.toml`, walking up from `start`.
+pub fn project_path(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(toml: &str) -> (Config, Vec<String>) {
+        toml::from_str::<Config>(toml).unwrap().trusted()
+    }
+
+    #[test]
+    fn project_keeps_emails() {
+        let (config, ignored) = project(r#"emails = ["anna@acme.com"]"#);
+        assert_eq!(config.emails, Some(vec!["anna@acme.com".to_string()]));
+        assert!(ignored.is_empty());
+    }
+
+    #[test]
+    fn project_ignores_public_links() {
+        let (config, ignored) = project(r#"public_links = "allow""#);
+        assert_eq!(config.public_links, None);
+        assert_eq!(ignored, ["public_links"]);
+    }
+
+    #[test]
+    fn project_ignores_allowed_email_domains() {
+        let (config, ignored) = project(r#"allowed_email_domains = ["evil.com"]"#);
+        assert_eq!(config.allowed_email_domains, None);
+        assert_eq!(ignored, ["allowed_email_domains"]);
+    }
+
+    #[test]
+    fn project_ignores_policy_file() {
+        let (config, ignored) = project(r#"policy_file = "lenient.toml""#);
+        assert_eq!(config.policy_file, None);
+        assert_eq!(ignored, ["policy_file"]);
+    }
+
+    #[test]
+    fn project_ignores_expiry_limits() {
+        let (config, ignored) = project("max_expires_in = \"30d\"\nclamp_expires_in = true");
+        assert_eq!(config.max_expires_in, None);
+        assert_eq!(config.clamp_expires_in, None);
+        assert_eq!(ignored, ["max_expires_in", "clamp_expires_in"]);
+    }
+
+    #[test]
+    fn project_ignores_codeowners() {
+        let (config, ignored) = project("[codeowners]\n\"@anna\" = \"anna@evil.com\"");
+        assert!(config.codeowners.is_empty());
+        assert_eq!(ignored, ["codeowners"]);
+    }
+
+    #[test]
+    fn project_ignores_command_clipboard_only() {
+        let (config, ignored) = project(r#"clipboard = "command:touch /tmp/pwned""#);
+        assert_eq!(config.clipboard, None);
+        assert_eq!(ignored, ["clipboard"]);
+
+        let (config, ignored) = project(r#"clipboard = "osc52""#);
+        assert_eq!(config.clipboard.as_deref(), Some("osc52"));
+        assert!(ignored.is_empty());
+    }
+
+    #[test]
+    fn project_ignores_untrusted_keys_in_profiles() {
+        let (config, ignored) = project("[profiles.work]\nvault = \"Work\"\npolicy_file = \"x\"");
+        let work = &config.profiles["work"];
+        assert_eq!(work.vault.as_deref(), Some("Work"));
+        assert_eq!(work.policy_file, None);
+        assert_eq!(ignored, ["profiles.work.policy_file"]);
+    }
+}
@@ -0,0 +1,286 @@
+//! Parsing of `.env` files into their `KEY=VALUE` assignments.
+//!
+//! The accepted syntax is the common denominator of dotenv implementations:
+//!
+//! - blank lines and lines starting with `#` are ignored
+//! - assignments may be prefixed with `export`
+//! - keys consist of letters, digits, `_`, `.`, and `-`, and do not start with a digit
+//! - unquoted values run to the end of the line or to a ` #` comment
+//! - single-quoted values are taken literally, double-quoted values understand `\n`, `\t`, `\"`,
+//!   and `\\` escapes, and both may span several lines
+
+use crate::error::{Result, ShareError};
+
+/// A single `KEY=VALUE` assignment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    pub key: String,
+    pub value: String,
+}
+
+/// The assignments in `content`, in file order.
+///
+/// Fails on the first syntax error, naming its line.
+pub fn parse(content: &str) -> Result<Vec<Variable>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut variables = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let number = index + 1;
+        let line = lines[index].trim();
+        index += 1;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line
+            .strip_prefix("export")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .map_or(line, str::trim_start);
+        let Some((key, rest)) = line.split_once('=') else {
+            return Err(error(number, "expected KEY=VALUE"));
+        };
+        let key = key.trim_end();
+        if !is_valid_key(key) {
+            return Err(error(number, format!("invalid key '{}'", key)));
+        }
+
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let (value, end) = quoted(&lines, number, &rest[1..], quote)?;
+                index = end;
+                value
+            }
+            _ => unquoted(rest),
+        };
+
+        variables.push(Variable {
+            key: key.to_string(),
+            value,
+        });
+    }
+    Ok(variables)
+}
+
+fn error(line: usize, message: impl Into<String>) -> ShareError {
+    ShareError::InvalidEnv {
+        line,
+        message: message.into(),
+    }
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// An unquoted value, which ends at a comment preceded by whitespace.
+fn unquoted(rest: &str) -> String {
+    let end = rest
+        .char_indices()
+        .find(|&(i, c)| c == '#' && rest[..i].ends_with(char::is_whitespace))
+        .map_or(rest.len(), |(i, _)| i);
+    rest[..end].trim_end().to_string()
+}
+
+/// Read a quoted value starting right after its opening quote on line `start` (1-based),
+/// continuing onto the following lines until the closing quote.
+///
+/// Returns the value and the index of the line after the one holding the closing quote.
+fn quoted(lines: &[&str], start: usize, first: &str, quote: char) -> Result<(String, usize)> {
+    let mut value = String::new();
+    let mut current = first;
+    let mut index = start;
+
+    loop {
+        let mut chars = current.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == quote {
+                let trailing = current[i + 1..].trim_start();
+                if !trailing.is_empty() && !trailing.starts_with('#') {
+                    return Err(error(
+                        index,
+                        format!("unexpected '{}' after closing quote", trailing),
+                    ));
+                }
+                return Ok((value, index));
+            }
+            if c == '\\' && quote == '"' {
+                match chars.next().map(|(_, escaped)| escaped) {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some(escaped) => {
+                        if !matches!(escaped, '"' | '\\' | '$') {
+                            value.push('\\');
+                        }
+                        value.push(escaped);
+                    }
+                    None => value.push('\\'),
+                }
+                continue;
+            }
+            value.push(c);
+        }
+
+        // The value continues on the next line
+        let Some(next) = lines.get(index) else {
+            return Err(error(start, format!("unterminated {} quote", quote)));
+        };
+        value.push('\n');
+        current = next;
+        index += 1;
+    }
+}
+
+/// Write `variables` as a `.env` file that [`parse`] reads back unchanged.
+///
+/// Values are double-quoted when they contain anything beyond plain characters, and keys that
+/// are not valid are made so by replacing the offending characters with `_`.
+pub fn format(variables: &[Variable]) -> String {
+    let mut content = String::new();
+    for variable in variables {
+        content.push_str(&format_key(&variable.key));
+        content.push('=');
+        content.push_str(&format_value(&variable.value));
+        content.push('\n');
+    }
+    content
+}
+
+fn format_key(key: &str) -> String {
+    let key: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        key
+    } else {
+        format!("_{}", key)
+    }
+}
+
+fn format_value(value: &str) -> String {
+    let plain = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-.,:/@+=%".contains(c));
+    if plain {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '$' => quoted.push_str("\\$"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(content: &str) -> Vec<(String, String)> {
+        parse(content)
+            .unwrap()
+            .into_iter()
+            .map(|variable| (variable.key, variable.value))
+            .collect()
+    }
+
+    #[test]
+    fn parse_skips_comments_and_export() {
+        let content = "# database\n\nexport DB_HOST=localhost # local\nDB_PORT = 5432\n";
+        assert_eq!(
+            pairs(content),
+            [
+                ("DB_HOST".to_string(), "localhost".to_string()),
+                ("DB_PORT".to_string(), "5432".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_reads_quoted_values() {
+        let content =
+            "A='literal \\n $HOME'\nB=\"line\\nnext \\\"quoted\\\"\"\nC=\"first\nsecond\"\n";
+        assert_eq!(
+            pairs(content),
+            [
+                ("A".to_string(), "literal \\n $HOME".to_string()),
+                ("B".to_string(), "line\nnext \"quoted\"".to_string()),
+                ("C".to_string(), "first\nsecond".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_keeps_a_hash_inside_a_value() {
+        assert_eq!(
+            pairs("URL=https://x.com/#top"),
+            [("URL".to_string(), "https://x.com/#top".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_names_the_line_of_an_error() {
+        for (content, line) in [
+            ("A=1\nnot an assignment", 2),
+            ("1KEY=value", 1),
+            ("A=\"open\nB=2", 1),
+        ] {
+            match parse(content) {
+                Err(ShareError::InvalidEnv { line: actual, .. }) => assert_eq!(actual, line),
+                other => panic!("{:?} parsed as {:?}", content, other),
+            }
+        }
+    }
+
+    #[test]
+    fn format_round_trips() {
+        let variables = vec![
+            Variable {
+                key: "PLAIN".to_string(),
+                value: "a-b_c".to_string(),
+            },
+            Variable {
+                key: "SPACED".to_string(),
+                value: "two words".to_string(),
+            },
+            Variable {
+                key: "TRICKY".to_string(),
+                value: "say \"hi\"\n$HOME\\".to_string(),
+            },
+        ];
+        assert_eq!(parse(&format(&variables)).unwrap(), variables);
+    }
+
+    #[test]
+    fn format_makes_keys_valid() {
+        let variables = [Variable {
+            key: "1 key".to_string(),
+            value: "x".to_string(),
+        }];
+        assert_eq!(format(&variables), "_1_key=x\n");
+    }
+}
@@ -0,0 +1,51 @@
+//! Composing the shared content in the user's editor.
+
+use crate::error::{Result, ShareError};
+use std::io::Write;
+use std::process::Command;
+
+/// The editor used when neither `VISUAL` nor `EDITOR` is set.
+#[cfg(windows)]
+pub const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+pub const DEFAULT_EDITOR: &str = "vi";
+
+/// The editor command from `VISUAL` or `EDITOR`, falling back to [`DEFAULT_EDITOR`].
+pub fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Open `initial` in the editor and return the saved content once it exits.
+///
+/// The content lives in a temporary file only the current user can read, which is removed
+/// afterwards. The editor command may include arguments, e.g. `code --wait`.
+pub fn edit(initial: &str) -> Result<String> {
+    let editor = editor();
+    let error = |message: String| ShareError::Editor {
+        editor: editor.clone(),
+        message,
+    };
+
+    let mut file = tempfile::Builder::new()
+        .prefix("share-1password-")
+        .suffix(".txt")
+        .tempfile()?;
+    file.write_all(initial.as_bytes())?;
+    file.flush()?;
+
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+    let status = Command::new(program)
+        .args(words)
+        .arg(file.path())
+        .status()
+        .map_err(|err| error(err.to_string()))?;
+    if !status.success() {
+        return Err(error(format!("exited with {}", status)));
+    }
+    Ok(std::fs::read_to_string(file.path())?)
+}
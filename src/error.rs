@@ -0,0 +1,236 @@
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use thiserror::Error;
+
+/// Every way sharing a note can fail, grouped by failure class.
+///
+/// Each class maps to its own process exit code so scripts can branch on the result.
+#[derive(Debug, Error)]
+pub enum ShareError {
+    #[error("No input text provided. Please provide text via stdin.\nUsage example: cat .env | share-1password")]
+    EmptyInput,
+
+    #[error("Invalid configuration in {}: {message}", path.display())]
+    Config { path: PathBuf, message: String },
+
+    #[error("Unknown profile '{profile}'. Available profiles: {available}")]
+    UnknownProfile { profile: String, available: String },
+
+    #[error("No KEY=VALUE assignments found in the input. Leave out --structured to share it as a note.")]
+    NoVariables,
+
+    #[error("Every variable was left out by --only, --exclude, and --match.")]
+    AllKeysFiltered,
+
+    #[error("Invalid regular expression '{pattern}': {message}")]
+    InvalidRegex { pattern: String, message: String },
+
+    #[error("Invalid .env syntax on line {line}: {message}.")]
+    InvalidEnv { line: usize, message: String },
+
+    #[error("Invalid {format} input: {message}")]
+    InvalidInput { format: String, message: String },
+
+    #[error("Invalid compose file {}: {message}", path.display())]
+    Compose { path: PathBuf, message: String },
+
+    #[error("Cannot share {}: {message}.", path.display())]
+    InputFile { path: PathBuf, message: String },
+
+    #[error("Invalid policy {}: {message}", path.display())]
+    Policy { path: PathBuf, message: String },
+
+    #[error("Invalid manifest {}: {message}", path.display())]
+    Manifest { path: PathBuf, message: String },
+
+    #[error("Cannot use the {preset} preset: {message}.")]
+    Preset { preset: String, message: String },
+
+    #[error("Invalid grant '{spec}'. Use group:NAME[:PERMISSION], where PERMISSION is read (the default), write, or manage.")]
+    InvalidGrant { spec: String },
+
+    #[error("Invalid section '{spec}'. Use LABEL=PATTERN[,PATTERN...], e.g. \"Database=DB_*\".")]
+    InvalidSection { spec: String },
+
+    #[error("Invalid duration '{value}'. Use a number of seconds, minutes, hours, days, or weeks, e.g. 7d, 36h, 1w2d, \"2 weeks\", or \"tomorrow\", and at most 30 days for share links.")]
+    InvalidDuration { value: String },
+
+    #[error("Invalid placeholder '{{{placeholder}}}' in title format '{template}'. Use {{dir}}, {{date}}, {{time}}, {{user}}, {{host}}, {{branch}}, or a strftime format such as {{%Y-%m-%d}}.")]
+    InvalidTitleFormat {
+        template: String,
+        placeholder: String,
+    },
+
+    #[error("Invalid placeholder '{{{placeholder}}}' in output template '{template}'. Use {{url}}, {{title}}, {{vault}}, {{item_id}}, {{expires_at}}, or {{recipients}}.")]
+    InvalidOutputTemplate {
+        template: String,
+        placeholder: String,
+    },
+
+    #[error("Failed to execute 1Password CLI: {0}")]
+    OpUnavailable(#[source] io::Error),
+
+    #[error("{feature} requires op >= {required}, you have {installed}. Update the 1Password CLI: https://developer.1password.com/docs/cli/get-started/")]
+    OpTooOld {
+        feature: String,
+        required: String,
+        installed: String,
+    },
+
+    #[error("{failed} of the checks failed.")]
+    ChecksFailed { failed: usize },
+
+    #[error("1Password CLI is not signed in. Please sign in first using 'op signin'.")]
+    NotSignedIn,
+
+    #[error("Error creating vault '{vault}'.\n{stderr}")]
+    VaultCreate { vault: String, stderr: String },
+
+    #[error("Vault '{vault}' is not accessible to the service account, and service accounts cannot create vaults. Create it in 1Password and grant the service account access.\n{stderr}")]
+    ServiceAccountVault { vault: String, stderr: String },
+
+    #[error("Error getting {category} template.\n{stderr}")]
+    Template { category: String, stderr: String },
+
+    #[error("Error creating the item in 1Password.\n{stderr}")]
+    ItemCreate { stderr: String },
+
+    #[error("Failed to get item ID.")]
+    MissingItemId,
+
+    #[error("Error sharing the item.\n{stderr}")]
+    Share { stderr: String },
+
+    #[error("Error {action}.\n{stderr}")]
+    Op { action: String, stderr: String },
+
+    #[error("Not sharing: {reason}.")]
+    Blocked { reason: String },
+
+    #[error("'{email}' is not a valid email address.")]
+    InvalidEmail { email: String },
+
+    #[error("No member of the 1Password account matches the recipient '{recipient}'.")]
+    UnknownRecipient { recipient: String },
+
+    #[error("The recipient '{recipient}' matches several members of the 1Password account: {matches}. Use their email address instead.")]
+    AmbiguousRecipient { recipient: String, matches: String },
+
+    #[error("Item '{item}' has no field '{field}'.")]
+    MissingField { item: String, field: String },
+
+    #[error("Item '{item}' was not created by share-1password.")]
+    NotSharedItem { item: String },
+
+    #[error("Item '{item}' is still accessible after revoking its share links.")]
+    RevokeFailed { item: String },
+
+    #[error("Failed to copy the link to the clipboard: {0}")]
+    Clipboard(#[from] arboard::Error),
+
+    #[error("Failed to copy the link through the terminal (OSC 52): {0}")]
+    Osc52(#[source] io::Error),
+
+    #[error("Failed to copy the link with {command}: {message}")]
+    ClipboardCommand { command: String, message: String },
+
+    #[error("Failed to read the clipboard: {0}")]
+    ClipboardRead(arboard::Error),
+
+    #[error("1Password Connect request failed: {0}")]
+    Connect(#[from] reqwest::Error),
+
+    #[error("--output github-actions needs the GITHUB_OUTPUT file, which GitHub Actions sets in every step.")]
+    GithubOutput,
+
+    #[error("Self-update failed: {message}.")]
+    Update { message: String },
+
+    #[error("Unknown configuration key '{key}'. Valid keys: {}", crate::config::KEYS.join(", "))]
+    UnknownConfigKey { key: String },
+
+    #[error("Invalid value '{value}' for configuration key '{key}'. Expected one of: {expected}")]
+    InvalidConfigValue {
+        key: String,
+        value: String,
+        expected: String,
+    },
+
+    #[error("Editor '{editor}' failed: {message}")]
+    Editor { editor: String, message: String },
+
+    #[error("Prompt failed: {0}")]
+    Prompt(#[from] dialoguer::Error),
+
+    #[error("Cannot ask whether to {action} without a terminal. Pass --yes to go ahead.")]
+    ConfirmationRequired { action: String },
+
+    #[error("Invalid JSON from 1Password CLI: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl ShareError {
+    /// The process exit code for this failure class.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ShareError::Io(_)
+            | ShareError::Json(_)
+            | ShareError::Prompt(_)
+            | ShareError::ConfirmationRequired { .. }
+            | ShareError::Editor { .. }
+            | ShareError::ChecksFailed { .. } => 1,
+            ShareError::EmptyInput
+            | ShareError::NoVariables
+            | ShareError::AllKeysFiltered
+            | ShareError::InvalidRegex { .. }
+            | ShareError::InvalidEnv { .. }
+            | ShareError::InvalidInput { .. }
+            | ShareError::Compose { .. }
+            | ShareError::InputFile { .. }
+            | ShareError::Manifest { .. }
+            | ShareError::Preset { .. }
+            | ShareError::InvalidSection { .. }
+            | ShareError::InvalidGrant { .. }
+            | ShareError::InvalidDuration { .. }
+            | ShareError::InvalidTitleFormat { .. }
+            | ShareError::InvalidOutputTemplate { .. }
+            | ShareError::InvalidEmail { .. }
+            | ShareError::UnknownRecipient { .. }
+            | ShareError::AmbiguousRecipient { .. }
+            | ShareError::MissingField { .. }
+            | ShareError::GithubOutput => 2,
+            ShareError::OpUnavailable(_) | ShareError::OpTooOld { .. } => 3,
+            ShareError::NotSignedIn => 4,
+            ShareError::VaultCreate { .. } | ShareError::ServiceAccountVault { .. } => 5,
+            ShareError::Template { .. } => 6,
+            ShareError::ItemCreate { .. } | ShareError::MissingItemId => 7,
+            ShareError::Share { .. } => 8,
+            ShareError::Clipboard(_)
+            | ShareError::Osc52(_)
+            | ShareError::ClipboardCommand { .. }
+            | ShareError::ClipboardRead(_) => 9,
+            ShareError::Connect(_) => 10,
+            ShareError::Config { .. }
+            | ShareError::UnknownProfile { .. }
+            | ShareError::UnknownConfigKey { .. }
+            | ShareError::InvalidConfigValue { .. }
+            | ShareError::Policy { .. } => 11,
+            ShareError::Op { .. } => 12,
+            ShareError::NotSharedItem { .. } | ShareError::RevokeFailed { .. } => 13,
+            ShareError::Blocked { .. } => 14,
+            ShareError::Update { .. } => 15,
+        }
+    }
+}
+
+impl From<ShareError> for ExitCode {
+    fn from(err: ShareError) -> Self {
+        ExitCode::from(err.exit_code())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ShareError>;
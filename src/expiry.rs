@@ -0,0 +1,167 @@
+//! Share link expiry durations, in the `op item share --expires-in` format.
+
+use crate::error::{Result, ShareError};
+use chrono::{DateTime, Duration, Utc};
+
+/// The longest `op` lets a share link stay valid.
+pub const MAX_LINK_DAYS: i64 = 30;
+
+/// Parse a duration such as `7d`, `36h`, `1w2d`, `2 weeks`, or `tomorrow` into its length, or
+/// `None` if it is not one or too long to represent.
+pub fn parse(expires_in: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    for (amount, unit) in parts(expires_in)? {
+        let part = match unit {
+            's' => Duration::try_seconds(amount)?,
+            'm' => Duration::try_minutes(amount)?,
+            'h' => Duration::try_hours(amount)?,
+            'd' => Duration::try_days(amount)?,
+            _ => Duration::try_weeks(amount)?,
+        };
+        total = total.checked_add(&part)?;
+    }
+    Some(total).filter(|total| *total > Duration::zero())
+}
+
+/// A share link expiry written the way `op` expects it, or `None` if it is not a duration or
+/// longer than the [`MAX_LINK_DAYS`] `op` allows.
+pub fn link_expiry(expires_in: &str) -> Option<String> {
+    let max = Duration::try_days(MAX_LINK_DAYS)?;
+    parse(expires_in)
+        .filter(|duration| *duration <= max)
+        .and(normalize(expires_in))
+}
+
+/// The duration written the way `op` expects it, e.g. `2w` for `2 weeks` or `1d12h` for
+/// `1 day and 12 hours`, or `None` if it is not a duration.
+pub fn normalize(expires_in: &str) -> Option<String> {
+    parse(expires_in)?;
+    Some(
+        parts(expires_in)?
+            .into_iter()
+            .map(|(amount, unit)| format!("{}{}", amount, unit))
+            .collect(),
+    )
+}
+
+/// The amounts and units (`s`, `m`, `h`, `d`, or `w`) making up a duration.
+///
+/// Units may be written out (`2 weeks`, `1 hour`, `90 mins`), and `a`/`an` stand for one. Parts
+/// can be separated by spaces, commas, or `and`, and the duration may start with `in`.
+fn parts(expires_in: &str) -> Option<Vec<(i64, char)>> {
+    let expires_in = expires_in.trim().to_lowercase();
+    if expires_in == "tomorrow" {
+        return Some(vec![(1, 'd')]);
+    }
+
+    // Split into runs of digits and of letters, e.g. `1w2d` into 1, w, 2, d
+    let mut tokens: Vec<String> = Vec::new();
+    for c in expires_in.chars() {
+        if c.is_whitespace() || c == ',' {
+            tokens.push(String::new());
+            continue;
+        }
+        if !c.is_ascii_alphanumeric() {
+            return None;
+        }
+        match tokens.last_mut() {
+            Some(token)
+                if token
+                    .chars()
+                    .last()
+                    .is_some_and(|last| last.is_ascii_digit() == c.is_ascii_digit()) =>
+            {
+                token.push(c)
+            }
+            _ => tokens.push(c.to_string()),
+        }
+    }
+    let mut tokens = tokens
+        .into_iter()
+        .filter(|token| !token.is_empty() && token != "and")
+        .peekable();
+    if tokens.peek().map(String::as_str) == Some("in") {
+        tokens.next();
+    }
+
+    let mut parts = Vec::new();
+    while let Some(amount) = tokens.next() {
+        let amount = match amount.as_str() {
+            "a" | "an" => 1,
+            digits => digits.parse().ok()?,
+        };
+        let unit = match tokens.next()?.as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => 's',
+            "m" | "min" | "mins" | "minute" | "minutes" => 'm',
+            "h" | "hr" | "hrs" | "hour" | "hours" => 'h',
+            "d" | "day" | "days" => 'd',
+            "w" | "wk" | "wks" | "week" | "weeks" => 'w',
+            _ => return None,
+        };
+        parts.push((amount, unit));
+    }
+    Some(parts).filter(|parts| !parts.is_empty())
+}
+
+/// The moment a link generated now with `expires_in` stops working, or `None` if the expiry
+/// cannot be interpreted. One too far out to represent is [`ShareError::InvalidDuration`].
+pub fn expires_at(expires_in: &str) -> Result<Option<DateTime<Utc>>> {
+    let Some(duration) = parse(expires_in) else {
+        return Ok(None);
+    };
+    Utc::now()
+        .checked_add_signed(duration)
+        .map(Some)
+        .ok_or_else(|| ShareError::InvalidDuration {
+            value: expires_in.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_understands_short_and_written_out_units() {
+        assert_eq!(parse("7d"), Duration::try_days(7));
+        assert_eq!(parse("1w2d"), Duration::try_days(9));
+        assert_eq!(parse("in 1 day and 12 hours"), Duration::try_hours(36));
+        assert_eq!(parse("an hour, 30 mins"), Duration::try_minutes(90));
+        assert_eq!(parse("Tomorrow"), Duration::try_days(1));
+    }
+
+    #[test]
+    fn parse_refuses_anything_else() {
+        for expires_in in ["", "0d", "7", "d", "7 fortnights", "-1d", "1.5h"] {
+            assert_eq!(parse(expires_in), None, "{:?}", expires_in);
+        }
+    }
+
+    #[test]
+    fn parse_refuses_overflowing_durations() {
+        assert_eq!(parse("9223372036854775807w"), None);
+        assert_eq!(parse("100000000000000w 100000000000000w"), None);
+    }
+
+    #[test]
+    fn normalize_writes_the_op_format() {
+        assert_eq!(normalize("2 weeks").as_deref(), Some("2w"));
+        assert_eq!(normalize("1 day and 12 hours").as_deref(), Some("1d12h"));
+        assert_eq!(normalize("soon"), None);
+    }
+
+    #[test]
+    fn link_expiry_is_at_most_30_days() {
+        assert_eq!(link_expiry("30 days").as_deref(), Some("30d"));
+        assert_eq!(link_expiry("4w2d").as_deref(), Some("4w2d"));
+        assert_eq!(link_expiry("31d"), None);
+        assert_eq!(link_expiry("5w"), None);
+    }
+
+    #[test]
+    fn expires_at_is_none_for_an_unknown_expiry() {
+        assert!(expires_at("whenever").unwrap().is_none());
+        let at = expires_at("1h").unwrap().unwrap();
+        assert!(at > Utc::now() + Duration::try_minutes(59).unwrap());
+    }
+}
@@ -0,0 +1,42 @@
+//! Selection of the variables that are shared.
+
+use crate::error::{Result, ShareError};
+use crate::sections::glob_match;
+use regex::Regex;
+
+/// Which variables to share, by glob patterns and a regular expression on their keys.
+#[derive(Debug, Clone, Default)]
+pub struct KeyFilter {
+    /// Share only keys matching one of these. Empty means every key.
+    pub only: Vec<String>,
+    /// Never share keys matching one of these.
+    pub exclude: Vec<String>,
+    /// Share only keys this expression finds a match in.
+    pub pattern: Option<Regex>,
+}
+
+impl KeyFilter {
+    /// Whether the filter lets every key through.
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.exclude.is_empty() && self.pattern.is_none()
+    }
+
+    /// Whether the variable `key` is shared.
+    pub fn keeps(&self, key: &str) -> bool {
+        let matches = |patterns: &[String]| patterns.iter().any(|pattern| glob_match(pattern, key));
+        (self.only.is_empty() || matches(&self.only))
+            && !matches(&self.exclude)
+            && self
+                .pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(key))
+    }
+}
+
+/// Compile the regular expression `pattern` for [`KeyFilter::pattern`].
+pub fn regex(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|err| ShareError::InvalidRegex {
+        pattern: pattern.to_string(),
+        message: err.to_string(),
+    })
+}
@@ -0,0 +1,67 @@
+//! Access to the item's vault granted to 1Password groups, for shares that should outlive an
+//! expiring link.
+
+use crate::error::{Result, ShareError};
+
+/// What a group may do in the vault.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Permission {
+    /// View items.
+    #[default]
+    Read,
+    /// View and edit items.
+    Write,
+    /// View and edit items, and manage who has access to the vault.
+    Manage,
+}
+
+impl Permission {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::Read => "read",
+            Permission::Write => "write",
+            Permission::Manage => "manage",
+        }
+    }
+
+    /// The permissions passed to `op vault group grant --permissions`.
+    pub fn op_permissions(self) -> &'static str {
+        match self {
+            Permission::Read => "allow_viewing",
+            Permission::Write => "allow_viewing,allow_editing",
+            Permission::Manage => "allow_viewing,allow_editing,allow_managing",
+        }
+    }
+}
+
+/// Access to the vault for a group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grant {
+    pub group: String,
+    pub permission: Permission,
+}
+
+impl Grant {
+    /// Parse a grant written as `group:NAME[:PERMISSION]`, e.g. `group:DevOps:read`. The
+    /// permission defaults to `read`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || ShareError::InvalidGrant {
+            spec: spec.to_string(),
+        };
+        let rest = spec.strip_prefix("group:").ok_or_else(invalid)?;
+        let (group, permission) = match rest.rsplit_once(':') {
+            Some((group, "read")) => (group, Permission::Read),
+            Some((group, "write")) => (group, Permission::Write),
+            Some((group, "manage")) => (group, Permission::Manage),
+            Some(_) => return Err(invalid()),
+            None => (rest, Permission::default()),
+        };
+        if group.trim().is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            group: group.trim().to_string(),
+            permission,
+        })
+    }
+}
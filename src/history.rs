@@ -0,0 +1,194 @@
+//! Local record of every share performed, stored as JSON lines in
+//! `$XDG_DATA_HOME/share-1password/history.jsonl` (by default `~/.local/share/share-1password`).
+
+use crate::config;
+use crate::error::Result;
+use crate::items::LinkStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+/// File name of the history store.
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// One generated share link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub item_id: String,
+    pub vault: String,
+    pub title: String,
+    /// Email addresses the link is restricted to. Empty means anyone with the link.
+    pub recipients: Vec<String>,
+    /// When the link stops working, if the expiry could be interpreted.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When the link was generated.
+    pub shared_at: DateTime<Utc>,
+    /// Name of the directory the share was made from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+impl Record {
+    /// The state of the link at `now`.
+    pub fn link_status(&self, now: DateTime<Utc>) -> LinkStatus {
+        LinkStatus::at(self.expires_at, now)
+    }
+
+    /// Whether `needle` occurs in the title, vault, or recipients, ignoring case.
+    pub fn matches(&self, needle: &str) -> bool {
+        let needle = needle.to_lowercase();
+        std::iter::once(&self.title)
+            .chain(std::iter::once(&self.vault))
+            .chain(&self.recipients)
+            .any(|haystack| haystack.to_lowercase().contains(&needle))
+    }
+}
+
+/// Aggregate figures over a set of records.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub shares: usize,
+    pub unique_recipients: usize,
+    /// Shares viewable by anyone with the link.
+    pub unrestricted: usize,
+    /// Projects by number of shares, most shared first.
+    pub projects: Vec<(String, usize)>,
+}
+
+impl Stats {
+    pub fn from_records(records: &[Record]) -> Self {
+        let recipients: BTreeSet<String> = records
+            .iter()
+            .flat_map(|record| &record.recipients)
+            .map(|email| email.to_lowercase())
+            .collect();
+
+        let mut projects: BTreeMap<&str, usize> = BTreeMap::new();
+        for record in records {
+            let project = record.project.as_deref().unwrap_or(&record.title);
+            *projects.entry(project).or_default() += 1;
+        }
+        let mut projects: Vec<(String, usize)> = projects
+            .into_iter()
+            .map(|(project, count)| (project.to_string(), count))
+            .collect();
+        projects.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Stats {
+            shares: records.len(),
+            unique_recipients: recipients.len(),
+            unrestricted: records
+                .iter()
+                .filter(|record| record.recipients.is_empty())
+                .count(),
+            projects,
+        }
+    }
+}
+
+/// The history store on disk.
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    /// The history store at its default location, if a home directory is known.
+    pub fn open() -> Option<Self> {
+        data_dir().map(|dir| Self::at(dir.join(HISTORY_FILE)))
+    }
+
+    /// A history store at `path`.
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All records, oldest first. Lines that cannot be parsed are skipped.
+    pub fn load(&self) -> Result<Vec<Record>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if let Ok(record) = serde_json::from_str(&line) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Add a record at the end of the store, leaving the lines already in it untouched.
+    ///
+    /// The record is written as one line in a single write to a file opened for appending, so
+    /// runs appending at the same time do not lose each other's records.
+    pub fn append(&self, record: Record) -> Result<()> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(dir)?;
+
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.path)?
+            .write_all(&line)?;
+        Ok(())
+    }
+
+    /// Remove every record for `item_id`, returning how many were removed. Lines that cannot be
+    /// parsed, e.g. written by a newer version, are kept.
+    pub fn remove_item(&self, item_id: &str) -> Result<usize> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err.into()),
+        };
+        let mut removed = 0;
+        let kept: Vec<&str> = content
+            .lines()
+            .filter(|line| {
+                let matches = serde_json::from_str::<Record>(line)
+                    .is_ok_and(|record| record.item_id == item_id);
+                removed += usize::from(matches);
+                !matches
+            })
+            .collect();
+        if removed > 0 {
+            self.write(&kept)?;
+        }
+        Ok(removed)
+    }
+
+    /// Replace the store with `lines`, via a temporary file so readers never see a partial write.
+    fn write(&self, lines: &[&str]) -> Result<()> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let mut tmp = NamedTempFile::new_in(dir)?;
+        for line in lines {
+            writeln!(tmp, "{}", line)?;
+        }
+        tmp.flush()?;
+        tmp.persist(&self.path).map_err(|err| err.error)?;
+        Ok(())
+    }
+}
+
+/// The directory holding this tool's data: `$XDG_DATA_HOME/share-1password`, falling back to
+/// `~/.local/share/share-1password`.
+pub fn data_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| config::home_dir().map(|home| home.join(".local").join("share")))
+        .map(|dir| dir.join("share-1password"))
+}
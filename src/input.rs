@@ -0,0 +1,229 @@
+//! Interpretation of the shared content according to its format.
+
+use crate::dotenv::{self, Variable};
+use crate::error::{Result, ShareError};
+use crate::tfvars;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::Value;
+
+/// A piece of content to share, such as one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    /// What the content is called, such as a file name. `None` for content piped in.
+    pub name: Option<String>,
+    pub content: String,
+}
+
+/// `bytes` as text, or back as they are if they are binary: not UTF-8, or containing NUL bytes.
+pub fn decode(bytes: Vec<u8>) -> std::result::Result<String, Vec<u8>> {
+    match String::from_utf8(bytes) {
+        Ok(text) if !text.contains('\0') => Ok(text),
+        Ok(text) => Err(text.into_bytes()),
+        Err(err) => Err(err.into_bytes()),
+    }
+}
+
+/// How the shared content is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Opaque text, stored as is. Read as a `.env` file when variables are needed.
+    #[default]
+    Raw,
+    Env,
+    Json,
+    Yaml,
+    Toml,
+    /// Terraform variable definitions, as in `terraform.tfvars`.
+    Tfvars,
+    /// A Kubernetes Secret manifest, as printed by `kubectl get secret NAME -o yaml`.
+    K8sSecret,
+}
+
+impl InputFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InputFormat::Raw => "raw",
+            InputFormat::Env => "env",
+            InputFormat::Json => "JSON",
+            InputFormat::Yaml => "YAML",
+            InputFormat::Toml => "TOML",
+            InputFormat::Tfvars => "tfvars",
+            InputFormat::K8sSecret => "Kubernetes Secret",
+        }
+    }
+
+    /// Validate `content` and return it in the form it is stored in a note, which is in the
+    /// [`stored_as`](Self::stored_as) format.
+    ///
+    /// JSON is pretty-printed, and YAML and TOML are converted to `KEY=VALUE` lines so the
+    /// recipient can use them as a `.env` file. Env and tfvars files are kept as written, so
+    /// comments survive.
+    pub fn normalize(self, content: &str) -> Result<String> {
+        match self {
+            InputFormat::Raw => Ok(content.to_string()),
+            InputFormat::Env | InputFormat::Tfvars => {
+                self.variables(content)?;
+                Ok(content.to_string())
+            }
+            InputFormat::Json => Ok(serde_json::to_string_pretty(&self.document(content)?)?),
+            InputFormat::Yaml | InputFormat::Toml | InputFormat::K8sSecret => {
+                Ok(dotenv::format(&self.variables(content)?))
+            }
+        }
+    }
+
+    /// The format of the content after [`normalize`](Self::normalize).
+    pub fn stored_as(self) -> InputFormat {
+        match self {
+            InputFormat::Yaml | InputFormat::Toml | InputFormat::K8sSecret => InputFormat::Env,
+            other => other,
+        }
+    }
+
+    /// The key/value pairs in `content`.
+    ///
+    /// Documents must be a mapping at the top level. JSON is flattened into one pair per scalar,
+    /// named by its path with dots, e.g. `credentials.client_id` or `scopes.0`. For YAML and TOML,
+    /// scalar values are used as written and nested values as compact JSON.
+    pub fn variables(self, content: &str) -> Result<Vec<Variable>> {
+        if matches!(self, InputFormat::Raw | InputFormat::Env) {
+            return dotenv::parse(content);
+        }
+        if self == InputFormat::Tfvars {
+            return tfvars::parse(content);
+        }
+        if self == InputFormat::K8sSecret {
+            return self.secret_variables(content);
+        }
+        let Value::Object(entries) = self.document(content)? else {
+            return Err(self.invalid("expected a mapping of keys to values at the top level"));
+        };
+        if self == InputFormat::Json {
+            let mut variables = Vec::new();
+            for (key, value) in entries {
+                flatten(key, value, &mut variables);
+            }
+            return Ok(variables);
+        }
+        Ok(entries
+            .into_iter()
+            .map(|(key, value)| Variable {
+                key,
+                value: scalar(value),
+            })
+            .collect())
+    }
+
+    /// Parse a JSON, YAML, or TOML document.
+    fn document(self, content: &str) -> Result<Value> {
+        match self {
+            InputFormat::Json => {
+                serde_json::from_str(content).map_err(|err| self.invalid(err.to_string()))
+            }
+            InputFormat::Yaml | InputFormat::K8sSecret => {
+                serde_yaml::from_str(content).map_err(|err| self.invalid(err.to_string()))
+            }
+            InputFormat::Toml => content
+                .parse::<toml::Table>()
+                .map(|table| toml_to_json(toml::Value::Table(table)))
+                .map_err(|err| self.invalid(err.message().to_string())),
+            InputFormat::Raw | InputFormat::Env | InputFormat::Tfvars => {
+                unreachable!("not a document format")
+            }
+        }
+    }
+
+    /// The decoded `data` and `stringData` entries of a Secret manifest.
+    fn secret_variables(self, content: &str) -> Result<Vec<Variable>> {
+        let manifest = self.document(content)?;
+        if let Some(kind) = manifest.get("kind").and_then(Value::as_str) {
+            if kind != "Secret" {
+                return Err(self.invalid(format!("expected kind Secret, got {}", kind)));
+            }
+        }
+
+        let mut variables = Vec::new();
+        if let Some(data) = manifest.get("data").and_then(Value::as_object) {
+            for (key, value) in data {
+                let encoded = value.as_str().unwrap_or_default();
+                let decoded = BASE64
+                    .decode(encoded.trim())
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    .ok_or_else(|| {
+                        self.invalid(format!("data.{} is not base64-encoded text", key))
+                    })?;
+                variables.push(Variable {
+                    key: key.clone(),
+                    value: decoded,
+                });
+            }
+        }
+        // `stringData` is written in plain text and wins over `data`, as in Kubernetes
+        if let Some(data) = manifest.get("stringData").and_then(Value::as_object) {
+            for (key, value) in data {
+                variables.retain(|variable| &variable.key != key);
+                variables.push(Variable {
+                    key: key.clone(),
+                    value: scalar(value.clone()),
+                });
+            }
+        }
+        Ok(variables)
+    }
+
+    fn invalid(self, message: impl Into<String>) -> ShareError {
+        ShareError::InvalidInput {
+            format: self.as_str().to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Add a pair for every scalar in `value`, naming nested ones by their dotted path from `key`.
+fn flatten(key: String, value: Value, variables: &mut Vec<Variable>) {
+    match value {
+        Value::Object(entries) if !entries.is_empty() => {
+            for (child, value) in entries {
+                flatten(format!("{}.{}", key, child), value, variables);
+            }
+        }
+        Value::Array(values) if !values.is_empty() => {
+            for (index, value) in values.into_iter().enumerate() {
+                flatten(format!("{}.{}", key, index), value, variables);
+            }
+        }
+        value => variables.push(Variable {
+            key,
+            value: scalar(value),
+        }),
+    }
+}
+
+/// The display form of a value: strings without quotes, nested values as JSON.
+fn scalar(value: Value) -> String {
+    match value {
+        Value::String(value) => value,
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Convert TOML to JSON, writing dates and times in their TOML form.
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(value) => Value::String(value),
+        toml::Value::Integer(value) => value.into(),
+        toml::Value::Float(value) => value.into(),
+        toml::Value::Boolean(value) => value.into(),
+        toml::Value::Datetime(value) => Value::String(value.to_string()),
+        toml::Value::Array(values) => values.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
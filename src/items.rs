@@ -0,0 +1,167 @@
+//! Items created by this tool, found by their tool tag.
+
+use crate::error::{Result, ShareError};
+use crate::expiry;
+use crate::op::{object_id, DeleteMode, OpBackend, ShareOptions};
+use crate::template;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde_json::Value;
+
+/// Tag applied to every item created by this tool.
+pub const TOOL_TAG: &str = "share-1password";
+
+/// ID of the field recording when the item's share link expires.
+pub const EXPIRES_AT_FIELD: &str = "shareExpiresAt";
+
+/// ID of the field holding the description given with `--notes`.
+pub const DESCRIPTION_FIELD: &str = "shareDescription";
+
+/// Whether an item's share link still works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkStatus {
+    Active,
+    Expired,
+    /// The item does not record when its link expires.
+    Unknown,
+}
+
+impl LinkStatus {
+    /// The status of a link expiring at `expires_at`, as of `now`.
+    pub fn at(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        match expires_at {
+            Some(expires_at) if expires_at > now => LinkStatus::Active,
+            Some(_) => LinkStatus::Expired,
+            None => LinkStatus::Unknown,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinkStatus::Active => "active",
+            LinkStatus::Expired => "expired",
+            LinkStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// An item created by this tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedItem {
+    pub id: String,
+    pub title: String,
+    pub vault: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl SharedItem {
+    /// Parse an item as returned by `op item get --format=json` or the Connect API.
+    pub fn from_json(item: &Value) -> Option<Self> {
+        let vault = item.get("vault");
+        Some(Self {
+            id: object_id(item)?.to_string(),
+            title: item.get("title")?.as_str()?.to_string(),
+            vault: vault
+                .and_then(|vault| vault.get("name").or(vault.get("id")))
+                .and_then(|name| name.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            created_at: item
+                .get("created_at")
+                .or(item.get("createdAt"))
+                .and_then(|date| date.as_str())
+                .and_then(parse_time),
+            expires_at: template::field_value(item, EXPIRES_AT_FIELD).and_then(parse_time),
+        })
+    }
+
+    /// The state of the item's share link at `now`.
+    pub fn link_status(&self, now: DateTime<Utc>) -> LinkStatus {
+        LinkStatus::at(self.expires_at, now)
+    }
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// List the items in `vault` that were created by this tool, oldest first.
+pub fn list(backend: &dyn OpBackend, vault: &str) -> Result<Vec<SharedItem>> {
+    let mut items = Vec::new();
+    for summary in backend.list_items(vault, &[TOOL_TAG])? {
+        let Some(id) = object_id(&summary) else {
+            continue;
+        };
+        // Summaries do not include fields, so the expiry needs the full item
+        let item = backend.get_item(id, vault)?;
+        items.extend(SharedItem::from_json(&item));
+    }
+    items.sort_by_key(|item| item.created_at);
+    Ok(items)
+}
+
+/// Look up an item created by this tool by ID or title.
+pub fn find(backend: &dyn OpBackend, item: &str, vault: &str) -> Result<SharedItem> {
+    let json = backend.get_item(item, vault)?;
+    let tagged = json
+        .get("tags")
+        .and_then(|tags| tags.as_array())
+        .is_some_and(|tags| tags.iter().any(|tag| tag.as_str() == Some(TOOL_TAG)));
+    if !tagged {
+        return Err(ShareError::NotSharedItem {
+            item: item.to_string(),
+        });
+    }
+    SharedItem::from_json(&json).ok_or(ShareError::MissingItemId)
+}
+
+/// Stop the item's share links from working by moving it to the archive, then check that it is
+/// no longer reachable in its vault.
+pub fn revoke(backend: &dyn OpBackend, item: &SharedItem, vault: &str) -> Result<()> {
+    backend.delete_item(&item.id, vault, DeleteMode::Archive)?;
+    if backend.get_item(&item.id, vault).is_ok() {
+        return Err(ShareError::RevokeFailed {
+            item: item.title.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Generate a fresh share link for the item and record its new expiry on the item.
+///
+/// Returns the new link and when it expires, if the expiry could be interpreted.
+pub fn extend(
+    backend: &dyn OpBackend,
+    item: &SharedItem,
+    vault: &str,
+    options: &ShareOptions,
+) -> Result<(String, Option<DateTime<Utc>>)> {
+    let expires_at = expiry::expires_at(&options.expires_in)?;
+    let link = backend.share_item(&item.id, vault, options)?;
+    if let Some(expires_at) = expires_at {
+        backend.set_item_field(
+            &item.id,
+            vault,
+            EXPIRES_AT_FIELD,
+            &expires_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        )?;
+    }
+    Ok((link, expires_at))
+}
+
+/// The items whose share links expired more than `grace` before `now`. A grace period reaching
+/// past the end of time keeps every item.
+pub fn expired(items: Vec<SharedItem>, now: DateTime<Utc>, grace: Duration) -> Vec<SharedItem> {
+    items
+        .into_iter()
+        .filter(|item| {
+            item.expires_at.is_some_and(|expires_at| {
+                expires_at
+                    .checked_add_signed(grace)
+                    .is_some_and(|end| end < now)
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,54 @@
+//! Securely share notes with others using 1Password.
+//!
+//! The [`ShareBuilder`] drives the whole flow: it stores the content as a Secure Note in a vault
+//! and generates a share link for it.
+//!
+//! ```no_run
+//! use share_1password::ShareBuilder;
+//!
+//! let result = ShareBuilder::new("API_KEY=secret")
+//!     .vault("Shared Notes")
+//!     .expires_in("1d")
+//!     .recipients(["client@example.com"])
+//!     .share()?;
+//! println!("{}", result.link);
+//! # Ok::<(), share_1password::ShareError>(())
+//! ```
+
+pub mod archive;
+pub mod classify;
+pub mod clipboard;
+pub mod codeowners;
+pub mod compose;
+pub mod config;
+pub mod dotenv;
+pub mod editor;
+pub mod error;
+pub mod expiry;
+pub mod filter;
+pub mod git;
+pub mod grant;
+pub mod history;
+pub mod input;
+pub mod items;
+pub mod manifest;
+pub mod op;
+pub mod output;
+pub mod pem;
+pub mod policy;
+pub mod preset;
+pub mod recent;
+pub mod recipients;
+pub mod scan;
+pub mod secrets;
+pub mod sections;
+pub mod share;
+pub mod template;
+pub mod tfvars;
+pub mod title;
+pub mod transfer;
+pub mod update;
+
+pub use config::Config;
+pub use error::{Result, ShareError};
+pub use share::{ShareBuilder, ShareResult, Summary};
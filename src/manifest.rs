@@ -0,0 +1,68 @@
+//! Manifests describing several shares to make in one run.
+//!
+//! A manifest is a YAML file listing the files to share and where to share them:
+//!
+//! ```yaml
+//! shares:
+//!   - file: .env.staging
+//!     vault: Clients
+//!     emails: [client@example.com]
+//!     expires_in: 1d
+//!   - file: certs/server.pem
+//!     title: Staging TLS certificate
+//! ```
+//!
+//! Options missing from an entry fall back to the configuration.
+
+use crate::error::{Result, ShareError};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// A parsed manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub shares: Vec<ManifestEntry>,
+}
+
+/// One share listed in a manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ManifestEntry {
+    /// The file to share, relative to the manifest.
+    pub file: PathBuf,
+    /// The vault to store the item in.
+    pub vault: Option<String>,
+    /// Email addresses to share the item with.
+    pub emails: Option<Vec<String>>,
+    /// Expiration time for the share link.
+    pub expires_in: Option<String>,
+    /// A fixed item title.
+    pub title: Option<String>,
+    /// Description telling the recipient what the content is.
+    pub notes: Option<String>,
+    /// Tags added to the item.
+    pub tags: Vec<String>,
+}
+
+impl Manifest {
+    /// Read the manifest at `path`, resolving the files it lists against its directory.
+    pub fn load(path: &Path) -> Result<Self> {
+        let error = |message: String| ShareError::Manifest {
+            path: path.to_path_buf(),
+            message,
+        };
+        let contents = std::fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+        let mut manifest: Manifest =
+            serde_yaml::from_str(&contents).map_err(|err| error(err.to_string()))?;
+
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for (index, entry) in manifest.shares.iter_mut().enumerate() {
+            if entry.file.as_os_str().is_empty() {
+                return Err(error(format!("share {} has no file", index + 1)));
+            }
+            entry.file = dir.join(&entry.file);
+        }
+        Ok(manifest)
+    }
+}
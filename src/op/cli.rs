@@ -0,0 +1,520 @@
+//! [`OpBackend`] implementation driving the 1Password CLI (`op`).
+
+use super::{
+    object_id, Account, DeleteMode, Feature, OpBackend, OpVersion, ShareOptions, User, Vault,
+};
+use crate::error::{Result, ShareError};
+use crate::template;
+use serde_json::Value;
+use std::fs::File;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use std::sync::OnceLock;
+use tempfile::NamedTempFile;
+
+/// Environment variable that puts `op` into service account mode.
+pub const SERVICE_ACCOUNT_TOKEN_ENV: &str = "OP_SERVICE_ACCOUNT_TOKEN";
+
+/// Talks to 1Password through the locally installed `op` binary.
+#[derive(Debug, Clone, Default)]
+pub struct CliBackend {
+    service_account: bool,
+    account: Option<String>,
+    sign_in: bool,
+    /// The session handed out by `op signin`, as the `OP_SESSION_*` variable and token passed to
+    /// every later command.
+    session: OnceLock<(String, String)>,
+    version: OnceLock<Option<OpVersion>>,
+}
+
+impl CliBackend {
+    /// Create a backend, detecting service account mode from `OP_SERVICE_ACCOUNT_TOKEN`.
+    pub fn new() -> Self {
+        Self {
+            service_account: std::env::var_os(SERVICE_ACCOUNT_TOKEN_ENV)
+                .is_some_and(|token| !token.is_empty()),
+            account: None,
+            sign_in: false,
+            session: OnceLock::new(),
+            version: OnceLock::new(),
+        }
+    }
+
+    /// Run every command against `account` instead of the CLI's default account.
+    pub fn with_account(mut self, account: Option<String>) -> Self {
+        self.account = account;
+        self
+    }
+
+    /// Run `op signin` when the session has expired, instead of failing with
+    /// [`ShareError::NotSignedIn`]. Only for processes with a terminal to answer its prompts on.
+    pub fn with_sign_in(mut self, sign_in: bool) -> Self {
+        self.sign_in = sign_in;
+        self
+    }
+
+    /// Whether `op` authenticates with a service account token rather than an interactive sign-in.
+    pub fn is_service_account(&self) -> bool {
+        self.service_account
+    }
+
+    /// The version of the installed `op`, asked for once. `None` if it cannot be determined.
+    pub fn version(&self) -> Option<OpVersion> {
+        *self.version.get_or_init(|| {
+            let output = Command::new("op").arg("--version").output().ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            OpVersion::parse(&String::from_utf8_lossy(&output.stdout))
+        })
+    }
+
+    /// The accounts the CLI is set up for, whether or not they are signed in.
+    pub fn list_accounts(&self) -> Result<Vec<Account>> {
+        let list_output = output(
+            Command::new("op")
+                .arg("account")
+                .arg("list")
+                .arg("--format=json"),
+        )?;
+
+        if !list_output.status.success() {
+            return Err(ShareError::Op {
+                action: "listing accounts".to_string(),
+                stderr: stderr(&list_output),
+            });
+        }
+
+        let accounts: Vec<Value> = serde_json::from_slice(&list_output.stdout)?;
+        Ok(accounts.iter().filter_map(Account::from_json).collect())
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new("op");
+        if let Some(account) = &self.account {
+            command.arg("--account").arg(account);
+        }
+        // Through the environment rather than `--session`, where other users could read it
+        if let Some((variable, token)) = self.session.get() {
+            command.env(variable, token);
+        }
+        command
+    }
+
+    /// Sign in with `op signin`, which prompts on the terminal, and keep the session it prints
+    /// for the commands that follow.
+    fn sign_in(&self) -> Result<()> {
+        crate::info!("1Password CLI is not signed in, signing in...");
+        // Prompts are answered on the terminal, as stdin may be the piped content
+        let tty = File::open("/dev/tty").map_err(|_| ShareError::NotSignedIn)?;
+        let signin = crate::output::suspend(|| {
+            self.command()
+                .arg("signin")
+                .stdin(tty)
+                .stderr(Stdio::inherit())
+                .output()
+        })
+        .map_err(ShareError::OpUnavailable)?;
+        if !signin.status.success() {
+            return Err(ShareError::NotSignedIn);
+        }
+
+        // With the desktop app integration there is no token, the app holds the session
+        if let Some(session) = session(&signin.stdout) {
+            let _ = self.session.set(session);
+        }
+        Ok(())
+    }
+}
+
+/// The `OP_SESSION_*` variable and token in the output of `op signin`, which sets it in the
+/// syntax of the user's shell, e.g. `export OP_SESSION_my="..."` or `$env:OP_SESSION_my="..."`.
+pub(super) fn session(stdout: &[u8]) -> Option<(String, String)> {
+    let stdout = String::from_utf8_lossy(stdout);
+    let start = stdout.find("OP_SESSION_")?;
+    let rest = &stdout[start..];
+    let end = rest.find(|c: char| c == '=' || c == '"' || c.is_whitespace())?;
+    let (variable, rest) = rest.split_at(end);
+    let token = rest.split('"').nth(1)?;
+    (!token.is_empty()).then(|| (variable.to_string(), token.to_string()))
+}
+
+/// Run an `op` command, mapping a missing or unexecutable binary to [`ShareError::OpUnavailable`].
+pub(super) fn output(command: &mut Command) -> Result<Output> {
+    command.output().map_err(ShareError::OpUnavailable)
+}
+
+pub(super) fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+impl OpBackend for CliBackend {
+    fn ensure_signed_in(&self) -> Result<()> {
+        // Service accounts have no interactive session to check
+        if self.service_account {
+            return Ok(());
+        }
+
+        let status = self
+            .command()
+            .arg("whoami")
+            .arg("--format=json")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(ShareError::OpUnavailable)?;
+
+        if status.success() {
+            return Ok(());
+        }
+        if !self.sign_in {
+            return Err(ShareError::NotSignedIn);
+        }
+        self.sign_in()
+    }
+
+    fn check_share_options(&self, options: &ShareOptions) -> Result<()> {
+        let version = self.version();
+        Feature::ItemShare.require(version)?;
+        if !options.emails.is_empty() {
+            Feature::ShareEmails.require(version)?;
+        }
+        if options.view_once {
+            Feature::ViewOnce.require(version)?;
+        }
+        Ok(())
+    }
+
+    fn list_vaults(&self) -> Result<Vec<Vault>> {
+        let list_output = output(self.command().arg("vault").arg("list").arg("--format=json"))?;
+
+        if !list_output.status.success() {
+            return Err(ShareError::Op {
+                action: "listing vaults".to_string(),
+                stderr: stderr(&list_output),
+            });
+        }
+
+        let vaults: Vec<Value> = serde_json::from_slice(&list_output.stdout)?;
+        Ok(vaults.iter().filter_map(Vault::from_json).collect())
+    }
+
+    fn list_users(&self) -> Result<Vec<User>> {
+        let list_output = output(self.command().arg("user").arg("list").arg("--format=json"))?;
+
+        if !list_output.status.success() {
+            return Err(ShareError::Op {
+                action: "listing users".to_string(),
+                stderr: stderr(&list_output),
+            });
+        }
+
+        let users: Vec<Value> = serde_json::from_slice(&list_output.stdout)?;
+        Ok(users.iter().filter_map(User::from_json).collect())
+    }
+
+    fn ensure_vault(&self, vault: &str) -> Result<String> {
+        let vault_check = output(
+            self.command()
+                .arg("vault")
+                .arg("get")
+                .arg(vault)
+                .arg("--format=json"),
+        )?;
+
+        let vault_output = if vault_check.status.success() {
+            vault_check
+        } else if self.service_account {
+            return Err(ShareError::ServiceAccountVault {
+                vault: vault.to_string(),
+                stderr: stderr(&vault_check),
+            });
+        } else {
+            crate::info!("Vault '{}' does not exist, creating it...", vault);
+            let vault_create = output(
+                self.command()
+                    .arg("vault")
+                    .arg("create")
+                    .arg(vault)
+                    .arg("--format=json"),
+            )?;
+            if !vault_create.status.success() {
+                return Err(ShareError::VaultCreate {
+                    vault: vault.to_string(),
+                    stderr: stderr(&vault_create),
+                });
+            }
+            vault_create
+        };
+
+        let vault_json: Value = serde_json::from_slice(&vault_output.stdout)?;
+        Ok(object_id(&vault_json).unwrap_or(vault).to_string())
+    }
+
+    fn grant_group(&self, vault: &str, group: &str, permissions: &str) -> Result<()> {
+        let grant_output = output(
+            self.command()
+                .arg("vault")
+                .arg("group")
+                .arg("grant")
+                .arg("--vault")
+                .arg(vault)
+                .arg("--group")
+                .arg(group)
+                .arg("--permissions")
+                .arg(permissions)
+                .arg("--no-input"),
+        )?;
+
+        if !grant_output.status.success() {
+            return Err(ShareError::Op {
+                action: format!("granting group '{}' access to vault '{}'", group, vault),
+                stderr: stderr(&grant_output),
+            });
+        }
+        Ok(())
+    }
+
+    fn get_template(&self, category: &str) -> Result<Value> {
+        let template_output = output(
+            self.command()
+                .arg("item")
+                .arg("template")
+                .arg("get")
+                .arg(category),
+        )?;
+
+        if !template_output.status.success() {
+            return Err(ShareError::Template {
+                category: category.to_string(),
+                stderr: stderr(&template_output),
+            });
+        }
+
+        Ok(serde_json::from_slice(&template_output.stdout)?)
+    }
+
+    fn create_item(&self, title: &str, vault: &str, item: &Value) -> Result<String> {
+        // Tags are passed as a flag rather than in the template
+        let mut item = item.clone();
+        let tags = item
+            .as_object_mut()
+            .and_then(|item| item.remove("tags"))
+            .and_then(|tags| tags.as_array().cloned())
+            .unwrap_or_default();
+        let tags: Vec<&str> = tags.iter().filter_map(|tag| tag.as_str()).collect();
+
+        // op reads the filled-in template from a file
+        let tmp_template = NamedTempFile::new()?;
+        serde_json::to_writer(&tmp_template, &item)?;
+
+        let mut create_command = self.command();
+        create_command
+            .arg("item")
+            .arg("create")
+            .arg("--title")
+            .arg(title)
+            .arg("--vault")
+            .arg(vault)
+            .arg("--template")
+            .arg(tmp_template.path())
+            .arg("--format=json");
+        if !tags.is_empty() {
+            create_command.arg("--tags").arg(tags.join(","));
+        }
+
+        let item_create_output = output(&mut create_command)?;
+
+        if !item_create_output.status.success() {
+            return Err(ShareError::ItemCreate {
+                stderr: stderr(&item_create_output),
+            });
+        }
+
+        let item: Value = serde_json::from_slice(&item_create_output.stdout)?;
+        object_id(&item)
+            .map(str::to_string)
+            .ok_or(ShareError::MissingItemId)
+    }
+
+    fn create_document(
+        &self,
+        title: &str,
+        vault: &str,
+        file_name: &str,
+        content: &[u8],
+        tags: &[&str],
+    ) -> Result<String> {
+        // `-` makes op read the file contents from stdin
+        let mut create_command = self.command();
+        create_command
+            .arg("document")
+            .arg("create")
+            .arg("-")
+            .arg("--title")
+            .arg(title)
+            .arg("--vault")
+            .arg(vault)
+            .arg("--file-name")
+            .arg(file_name)
+            .arg("--format=json");
+        if !tags.is_empty() {
+            create_command.arg("--tags").arg(tags.join(","));
+        }
+
+        let mut child = create_command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(ShareError::OpUnavailable)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(content)?;
+        }
+        let document_create_output = child.wait_with_output()?;
+
+        if !document_create_output.status.success() {
+            return Err(ShareError::ItemCreate {
+                stderr: stderr(&document_create_output),
+            });
+        }
+
+        let document: Value = serde_json::from_slice(&document_create_output.stdout)?;
+        object_id(&document)
+            .map(str::to_string)
+            .ok_or(ShareError::MissingItemId)
+    }
+
+    fn list_items(&self, vault: &str, tags: &[&str]) -> Result<Vec<Value>> {
+        let mut list_command = self.command();
+        list_command
+            .arg("item")
+            .arg("list")
+            .arg("--vault")
+            .arg(vault)
+            .arg("--format=json");
+        if !tags.is_empty() {
+            list_command.arg("--tags").arg(tags.join(","));
+        }
+
+        let list_output = output(&mut list_command)?;
+
+        if !list_output.status.success() {
+            return Err(ShareError::Op {
+                action: format!("listing items in vault '{}'", vault),
+                stderr: stderr(&list_output),
+            });
+        }
+
+        Ok(serde_json::from_slice(&list_output.stdout)?)
+    }
+
+    fn get_item(&self, item_id: &str, vault: &str) -> Result<Value> {
+        let get_output = output(
+            self.command()
+                .arg("item")
+                .arg("get")
+                .arg(item_id)
+                .arg("--vault")
+                .arg(vault)
+                .arg("--format=json"),
+        )?;
+
+        if !get_output.status.success() {
+            return Err(ShareError::Op {
+                action: format!("getting item '{}'", item_id),
+                stderr: stderr(&get_output),
+            });
+        }
+
+        Ok(serde_json::from_slice(&get_output.stdout)?)
+    }
+
+    fn set_item_field(
+        &self,
+        item_id: &str,
+        vault: &str,
+        field_id: &str,
+        value: &str,
+    ) -> Result<()> {
+        // The value may be secret, so the edited item is read from a file like new ones are,
+        // rather than passed as an assignment on the command line
+        let mut item = self.get_item(item_id, vault)?;
+        template::set_field(&mut item, field_id, field_id, value);
+        let tmp_template = NamedTempFile::new()?;
+        serde_json::to_writer(&tmp_template, &item)?;
+
+        let edit_output = output(
+            self.command()
+                .arg("item")
+                .arg("edit")
+                .arg(item_id)
+                .arg("--vault")
+                .arg(vault)
+                .arg("--template")
+                .arg(tmp_template.path())
+                .arg("--format=json"),
+        )?;
+
+        if !edit_output.status.success() {
+            return Err(ShareError::Op {
+                action: format!("editing item '{}'", item_id),
+                stderr: stderr(&edit_output),
+            });
+        }
+        Ok(())
+    }
+
+    fn delete_item(&self, item_id: &str, vault: &str, mode: DeleteMode) -> Result<()> {
+        let mut delete_command = self.command();
+        delete_command
+            .arg("item")
+            .arg("delete")
+            .arg(item_id)
+            .arg("--vault")
+            .arg(vault);
+        if mode == DeleteMode::Archive {
+            delete_command.arg("--archive");
+        }
+
+        let delete_output = output(&mut delete_command)?;
+
+        if !delete_output.status.success() {
+            return Err(ShareError::Op {
+                action: format!("deleting item '{}'", item_id),
+                stderr: stderr(&delete_output),
+            });
+        }
+        Ok(())
+    }
+
+    fn share_item(&self, item_id: &str, vault: &str, options: &ShareOptions) -> Result<String> {
+        self.check_share_options(options)?;
+        let mut share_command = self.command();
+        share_command
+            .arg("item")
+            .arg("share")
+            .arg(item_id)
+            .arg("--vault")
+            .arg(vault)
+            .arg("--expires-in")
+            .arg(&options.expires_in);
+
+        for email in &options.emails {
+            share_command.arg("--emails").arg(email);
+        }
+        if options.view_once {
+            share_command.arg("--view-once");
+        }
+
+        let share_output = output(&mut share_command)?;
+
+        if !share_output.status.success() {
+            return Err(ShareError::Share {
+                stderr: stderr(&share_output),
+            });
+        }
+
+        Ok(String::from_utf8_lossy(&share_output.stdout).into_owned())
+    }
+}
@@ -0,0 +1,271 @@
+//! [`OpBackend`] implementation talking to a 1Password Connect server over its REST API.
+
+use super::{object_id, CliBackend, DeleteMode, OpBackend, ShareOptions, User, Vault};
+use crate::error::{Result, ShareError};
+use crate::template;
+use reqwest::blocking::{Client, RequestBuilder};
+use serde_json::{json, Value};
+
+/// Environment variable holding the Connect server URL.
+pub const HOST_ENV: &str = "OP_CONNECT_HOST";
+
+/// Environment variable holding the Connect access token.
+pub const TOKEN_ENV: &str = "OP_CONNECT_TOKEN";
+
+/// Talks to a 1Password Connect server.
+///
+/// Connect has no endpoint for share links, so links are still generated through the `op` CLI.
+#[derive(Debug, Clone)]
+pub struct ConnectBackend {
+    host: String,
+    token: String,
+    client: Client,
+    cli: CliBackend,
+}
+
+impl ConnectBackend {
+    pub fn new(host: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            host: host.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+            client: Client::new(),
+            cli: CliBackend::new(),
+        }
+    }
+
+    /// Generate share links against `account` instead of the CLI's default account.
+    pub fn with_account(mut self, account: Option<String>) -> Self {
+        self.cli = self.cli.with_account(account);
+        self
+    }
+
+    /// Build a backend from `OP_CONNECT_HOST` and `OP_CONNECT_TOKEN`, if both are set.
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var(HOST_ENV).ok().filter(|h| !h.is_empty())?;
+        let token = std::env::var(TOKEN_ENV).ok().filter(|t| !t.is_empty())?;
+        Some(Self::new(host, token))
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}/v1/{}", self.host, path))
+            .bearer_auth(&self.token)
+    }
+
+    /// Send a request, turning non-success responses into the `message` Connect reports.
+    fn send(&self, request: RequestBuilder) -> Result<std::result::Result<Value, String>> {
+        let response = request.send()?;
+        let status = response.status();
+        let body: Value = response.json().unwrap_or(Value::Null);
+        if status.is_success() {
+            return Ok(Ok(body));
+        }
+        let message = body
+            .get("message")
+            .and_then(|m| m.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| status.to_string());
+        Ok(Err(message))
+    }
+}
+
+impl OpBackend for ConnectBackend {
+    fn list_vaults(&self) -> Result<Vec<Vault>> {
+        let vaults = self
+            .send(self.request(reqwest::Method::GET, "vaults"))?
+            .map_err(|stderr| ShareError::Op {
+                action: "listing vaults".to_string(),
+                stderr,
+            })?;
+
+        Ok(vaults
+            .as_array()
+            .map(|vaults| vaults.iter().filter_map(Vault::from_json).collect())
+            .unwrap_or_default())
+    }
+
+    fn list_users(&self) -> Result<Vec<User>> {
+        // Connect only knows about vaults and items
+        self.cli.list_users()
+    }
+
+    fn ensure_vault(&self, vault: &str) -> Result<String> {
+        let filter = format!("name eq \"{}\"", vault);
+        let vaults = self
+            .send(
+                self.request(reqwest::Method::GET, "vaults")
+                    .query(&[("filter", filter)]),
+            )?
+            .map_err(|stderr| ShareError::VaultCreate {
+                vault: vault.to_string(),
+                stderr,
+            })?;
+
+        vaults
+            .as_array()
+            .and_then(|vaults| vaults.first())
+            .and_then(object_id)
+            .map(str::to_string)
+            .ok_or_else(|| ShareError::VaultCreate {
+                vault: vault.to_string(),
+                stderr: "1Password Connect cannot create vaults. Create it in 1Password and grant the Connect server access.".to_string(),
+            })
+    }
+
+    fn grant_group(&self, vault: &str, group: &str, permissions: &str) -> Result<()> {
+        // Connect cannot manage vault access
+        self.cli.grant_group(vault, group, permissions)
+    }
+
+    fn get_template(&self, category: &str) -> Result<Value> {
+        // Connect has no template endpoint
+        template::builtin(category).ok_or_else(|| ShareError::Template {
+            category: category.to_string(),
+            stderr: "No built-in template is available for 1Password Connect.".to_string(),
+        })
+    }
+
+    fn create_item(&self, title: &str, vault: &str, item: &Value) -> Result<String> {
+        let vault_id = self.ensure_vault(vault)?;
+
+        let mut body = item.clone();
+        body["title"] = json!(title);
+        body["vault"] = json!({ "id": vault_id });
+
+        let created = self
+            .send(
+                self.request(reqwest::Method::POST, &format!("vaults/{}/items", vault_id))
+                    .json(&body),
+            )?
+            .map_err(|stderr| ShareError::ItemCreate { stderr })?;
+
+        object_id(&created)
+            .map(str::to_string)
+            .ok_or(ShareError::MissingItemId)
+    }
+
+    fn create_document(
+        &self,
+        title: &str,
+        vault: &str,
+        file_name: &str,
+        content: &[u8],
+        tags: &[&str],
+    ) -> Result<String> {
+        // Connect can read files but not upload them
+        self.cli
+            .create_document(title, vault, file_name, content, tags)
+    }
+
+    fn list_items(&self, vault: &str, tags: &[&str]) -> Result<Vec<Value>> {
+        let vault_id = self.ensure_vault(vault)?;
+        let mut request = self.request(reqwest::Method::GET, &format!("vaults/{}/items", vault_id));
+        if !tags.is_empty() {
+            let filter = tags
+                .iter()
+                .map(|tag| format!("tag eq \"{}\"", tag))
+                .collect::<Vec<_>>()
+                .join(" and ");
+            request = request.query(&[("filter", filter)]);
+        }
+
+        let items = self.send(request)?.map_err(|stderr| ShareError::Op {
+            action: format!("listing items in vault '{}'", vault),
+            stderr,
+        })?;
+
+        Ok(items.as_array().cloned().unwrap_or_default())
+    }
+
+    fn get_item(&self, item_id: &str, vault: &str) -> Result<Value> {
+        let vault_id = self.ensure_vault(vault)?;
+        let error = |stderr: String| ShareError::Op {
+            action: format!("getting item '{}'", item_id),
+            stderr,
+        };
+
+        // Connect only fetches items by ID, so a title is looked up first
+        let filter = format!(
+            "title eq \"{}\"",
+            item_id.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        let titled = self
+            .send(
+                self.request(reqwest::Method::GET, &format!("vaults/{}/items", vault_id))
+                    .query(&[("filter", filter)]),
+            )?
+            .map_err(error)?;
+        let id = match titled.as_array().map(Vec::as_slice).unwrap_or_default() {
+            [] => item_id,
+            [item] => super::object_id(item).unwrap_or(item_id),
+            _ => {
+                return Err(error(format!(
+                    "Several items are titled '{}', give the ID of the one you mean.",
+                    item_id
+                )))
+            }
+        };
+
+        self.send(self.request(
+            reqwest::Method::GET,
+            &format!("vaults/{}/items/{}", vault_id, id),
+        ))?
+        .map_err(error)
+    }
+
+    fn set_item_field(
+        &self,
+        item_id: &str,
+        vault: &str,
+        field_id: &str,
+        value: &str,
+    ) -> Result<()> {
+        let vault_id = self.ensure_vault(vault)?;
+        let mut item = self.get_item(item_id, vault)?;
+        template::set_field(&mut item, field_id, field_id, value);
+
+        self.send(
+            self.request(
+                reqwest::Method::PUT,
+                &format!("vaults/{}/items/{}", vault_id, item_id),
+            )
+            .json(&item),
+        )?
+        .map_err(|stderr| ShareError::Op {
+            action: format!("editing item '{}'", item_id),
+            stderr,
+        })?;
+        Ok(())
+    }
+
+    fn delete_item(&self, item_id: &str, vault: &str, mode: DeleteMode) -> Result<()> {
+        // Connect has a single delete operation, which deletes for good
+        if mode == DeleteMode::Archive {
+            return Err(ShareError::Op {
+                action: format!("moving item '{}' to the archive", item_id),
+                stderr:
+                    "1Password Connect can only delete items for good. Use `delete --permanent` \
+                         to do so, or --backend cli to archive it."
+                        .to_string(),
+            });
+        }
+        let vault_id = self.ensure_vault(vault)?;
+        self.send(self.request(
+            reqwest::Method::DELETE,
+            &format!("vaults/{}/items/{}", vault_id, item_id),
+        ))?
+        .map_err(|stderr| ShareError::Op {
+            action: format!("deleting item '{}'", item_id),
+            stderr,
+        })?;
+        Ok(())
+    }
+
+    fn check_share_options(&self, options: &ShareOptions) -> Result<()> {
+        self.cli.check_share_options(options)
+    }
+
+    fn share_item(&self, item_id: &str, vault: &str, options: &ShareOptions) -> Result<String> {
+        self.cli.share_item(item_id, vault, options)
+    }
+}
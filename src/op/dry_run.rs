@@ -0,0 +1,295 @@
+//! [`OpBackend`] wrapper that prints the `op` commands that would change something instead of
+//! running them.
+
+use super::{DeleteMode, OpBackend, ShareOptions, User, Vault};
+use crate::classify;
+use crate::error::Result;
+use serde_json::Value;
+
+/// The ID reported for items that a dry run pretends to create.
+pub const DRY_RUN_ITEM_ID: &str = "<new item>";
+
+/// The link reported for shares that a dry run pretends to generate.
+pub const DRY_RUN_LINK: &str = "https://share.1password.com/s#<dry-run>";
+
+/// Reads through another backend, but only prints what it would create, change, or share.
+///
+/// Every field value it prints is masked.
+pub struct DryRunBackend {
+    inner: Box<dyn OpBackend>,
+    account: Option<String>,
+}
+
+impl DryRunBackend {
+    /// Wrap `inner`, printing commands as run against `account`.
+    pub fn new(inner: Box<dyn OpBackend>, account: Option<String>) -> Self {
+        Self { inner, account }
+    }
+
+    /// Print an `op` command line, followed by `details` if there are any.
+    fn print(&self, args: &[&str], details: Option<String>) {
+        let mut line = String::from("op");
+        if let Some(account) = &self.account {
+            line.push_str(&format!(" --account {}", quote(account)));
+        }
+        for arg in args {
+            line.push(' ');
+            line.push_str(&quote(arg));
+        }
+        // One write per command, so commands printed from several threads stay whole
+        crate::output::suspend(|| match details {
+            Some(details) => eprintln!("Would run: {}\n{}", line, details),
+            None => eprintln!("Would run: {}", line),
+        })
+    }
+}
+
+impl OpBackend for DryRunBackend {
+    fn ensure_signed_in(&self) -> Result<()> {
+        self.inner.ensure_signed_in()
+    }
+
+    fn check_share_options(&self, options: &ShareOptions) -> Result<()> {
+        self.inner.check_share_options(options)
+    }
+
+    fn list_vaults(&self) -> Result<Vec<Vault>> {
+        self.inner.list_vaults()
+    }
+
+    fn list_users(&self) -> Result<Vec<User>> {
+        self.inner.list_users()
+    }
+
+    fn ensure_vault(&self, vault: &str) -> Result<String> {
+        let existing = self
+            .inner
+            .list_vaults()?
+            .into_iter()
+            .find(|candidate| candidate.id == vault || candidate.name == vault);
+        match existing {
+            Some(existing) => Ok(existing.id),
+            None => {
+                self.print(&["vault", "create", vault, "--format=json"], None);
+                Ok(vault.to_string())
+            }
+        }
+    }
+
+    fn grant_group(&self, vault: &str, group: &str, permissions: &str) -> Result<()> {
+        self.print(
+            &[
+                "vault",
+                "group",
+                "grant",
+                "--vault",
+                vault,
+                "--group",
+                group,
+                "--permissions",
+                permissions,
+                "--no-input",
+            ],
+            None,
+        );
+        Ok(())
+    }
+
+    fn get_template(&self, category: &str) -> Result<Value> {
+        self.inner.get_template(category)
+    }
+
+    fn create_item(&self, title: &str, vault: &str, item: &Value) -> Result<String> {
+        let mut item = masked(item);
+        let tags: Vec<String> = item
+            .as_object_mut()
+            .and_then(|item| item.remove("tags"))
+            .and_then(|tags| tags.as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|tag| tag.as_str().map(str::to_string))
+            .collect();
+        let tags = tags.join(",");
+
+        let mut args = vec![
+            "item",
+            "create",
+            "--title",
+            title,
+            "--vault",
+            vault,
+            "--template",
+            "item.json",
+            "--format=json",
+        ];
+        if !tags.is_empty() {
+            args.extend(["--tags", &tags]);
+        }
+        let json = serde_json::to_string_pretty(&item)?;
+        self.print(&args, Some(format!("with item.json:\n{}", json)));
+        Ok(DRY_RUN_ITEM_ID.to_string())
+    }
+
+    fn create_document(
+        &self,
+        title: &str,
+        vault: &str,
+        file_name: &str,
+        content: &[u8],
+        tags: &[&str],
+    ) -> Result<String> {
+        let tags = tags.join(",");
+        let mut args = vec![
+            "document",
+            "create",
+            "-",
+            "--title",
+            title,
+            "--vault",
+            vault,
+            "--file-name",
+            file_name,
+            "--format=json",
+        ];
+        if !tags.is_empty() {
+            args.extend(["--tags", &tags]);
+        }
+        self.print(
+            &args,
+            Some(format!("with {} byte(s) on stdin", content.len())),
+        );
+        Ok(DRY_RUN_ITEM_ID.to_string())
+    }
+
+    fn list_items(&self, vault: &str, tags: &[&str]) -> Result<Vec<Value>> {
+        self.inner.list_items(vault, tags)
+    }
+
+    fn get_item(&self, item_id: &str, vault: &str) -> Result<Value> {
+        self.inner.get_item(item_id, vault)
+    }
+
+    fn set_item_field(
+        &self,
+        item_id: &str,
+        vault: &str,
+        field_id: &str,
+        value: &str,
+    ) -> Result<()> {
+        self.print(
+            &[
+                "item",
+                "edit",
+                item_id,
+                "--vault",
+                vault,
+                "--template",
+                "item.json",
+                "--format=json",
+            ],
+            Some(format!(
+                "with item.json setting {} to {}",
+                field_id,
+                classify::masked(value)
+            )),
+        );
+        Ok(())
+    }
+
+    fn delete_item(&self, item_id: &str, vault: &str, mode: DeleteMode) -> Result<()> {
+        let mut args = vec!["item", "delete", item_id, "--vault", vault];
+        if mode == DeleteMode::Archive {
+            args.push("--archive");
+        }
+        self.print(&args, None);
+        Ok(())
+    }
+
+    fn share_item(&self, item_id: &str, vault: &str, options: &ShareOptions) -> Result<String> {
+        let mut args = vec![
+            "item",
+            "share",
+            item_id,
+            "--vault",
+            vault,
+            "--expires-in",
+            &options.expires_in,
+        ];
+        for email in &options.emails {
+            args.extend(["--emails", email]);
+        }
+        if options.view_once {
+            args.push("--view-once");
+        }
+        self.print(&args, None);
+        Ok(DRY_RUN_LINK.to_string())
+    }
+}
+
+/// A copy of `item` with the values of all its fields masked: the shared content can end up in
+/// any of them, e.g. as one field per variable.
+fn masked(item: &Value) -> Value {
+    let mut item = item.clone();
+    if let Some(fields) = item.get_mut("fields").and_then(Value::as_array_mut) {
+        for field in fields {
+            if let Some(value) = field.get_mut("value") {
+                if let Some(text) = value.as_str().filter(|text| !text.is_empty()) {
+                    *value = Value::String(classify::masked(text));
+                }
+            }
+        }
+    }
+    item
+}
+
+/// `arg` quoted for a POSIX shell if it needs to be.
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_=.,/:@[]".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn masked_masks_every_field_value() {
+        let item = json!({
+            "title": "API keys",
+            "fields": [
+                { "id": "notesPlain", "purpose": "NOTES", "value": "A=1" },
+                { "id": "API_KEY", "type": "STRING", "value": "sk_live_abcdef" },
+                { "id": "password", "type": "CONCEALED", "value": "hunter2" },
+                { "id": "empty", "type": "STRING", "value": "" },
+            ]
+        });
+        let masked = masked(&item);
+        for (field, original) in
+            masked["fields"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .zip(["A=1", "sk_live_abcdef", "hunter2"])
+        {
+            assert_ne!(field["value"], original);
+        }
+        assert_eq!(masked["fields"][3]["value"], "");
+        assert_eq!(masked["title"], "API keys");
+    }
+
+    #[test]
+    fn quote_leaves_plain_arguments_alone() {
+        assert_eq!(quote("--vault"), "--vault");
+        assert_eq!(quote("Shared Notes"), "'Shared Notes'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote(""), "''");
+    }
+}
@@ -0,0 +1,624 @@
+//! [`OpBackend`] implementation for the 1Password CLI v1 (`op list items`, `op get item`, ...).
+//!
+//! Creating, reading, archiving, and deleting items is translated to the v1 commands, and items
+//! between the v2 JSON the rest of the crate expects and the v1 layout. Share links, editing
+//! items, and vault permissions have no v1 counterpart, so those operations fail with
+//! [`ShareError::OpTooOld`].
+
+use super::cli::{output, session, stderr};
+use super::{DeleteMode, Feature, OpBackend, OpVersion, ShareOptions, User, Vault};
+use crate::error::{Result, ShareError};
+use crate::template;
+use serde_json::{json, Map, Value};
+use std::fs::File;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use tempfile::NamedTempFile;
+
+/// The first `op` release with the v2 command syntax.
+pub const V2: OpVersion = OpVersion::new(2, 0, 0);
+
+/// The first v1 release that reads the details of a new item from a file with `--template`.
+pub const TEMPLATE_FILE: OpVersion = OpVersion::new(1, 8, 0);
+
+/// Talks to 1Password through an installed `op` v1 binary.
+#[derive(Debug, Clone)]
+pub struct LegacyCliBackend {
+    version: OpVersion,
+    account: Option<String>,
+    sign_in: bool,
+    /// The session handed out by `op signin`. See [`super::CliBackend`].
+    session: OnceLock<(String, String)>,
+}
+
+impl LegacyCliBackend {
+    /// Create a backend for the installed `op` of `version`.
+    pub fn new(version: OpVersion) -> Self {
+        Self {
+            version,
+            account: None,
+            sign_in: false,
+            session: OnceLock::new(),
+        }
+    }
+
+    /// Run every command against `account` instead of the CLI's default account.
+    pub fn with_account(mut self, account: Option<String>) -> Self {
+        self.account = account;
+        self
+    }
+
+    /// Run `op signin` when the session has expired. See [`super::CliBackend::with_sign_in`].
+    pub fn with_sign_in(mut self, sign_in: bool) -> Self {
+        self.sign_in = sign_in;
+        self
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new("op");
+        if let Some(account) = &self.account {
+            command.arg("--account").arg(account);
+        }
+        if let Some((variable, token)) = self.session.get() {
+            command.env(variable, token);
+        }
+        command
+    }
+
+    /// Run `command` and parse its JSON output, reporting a failure as [`ShareError::Op`].
+    fn json(&self, command: &mut Command, action: impl FnOnce() -> String) -> Result<Value> {
+        let command_output = output(command)?;
+        if !command_output.status.success() {
+            return Err(ShareError::Op {
+                action: action(),
+                stderr: stderr(&command_output),
+            });
+        }
+        Ok(serde_json::from_slice(&command_output.stdout)?)
+    }
+
+    /// The error for an operation v1 cannot perform.
+    fn unsupported(&self, feature: &str) -> ShareError {
+        ShareError::OpTooOld {
+            feature: feature.to_string(),
+            required: V2.to_string(),
+            installed: self.version.to_string(),
+        }
+    }
+}
+
+impl OpBackend for LegacyCliBackend {
+    fn ensure_signed_in(&self) -> Result<()> {
+        let status = self
+            .command()
+            .arg("get")
+            .arg("account")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(ShareError::OpUnavailable)?;
+        if status.success() {
+            return Ok(());
+        }
+        if !self.sign_in {
+            return Err(ShareError::NotSignedIn);
+        }
+
+        crate::info!("1Password CLI is not signed in, signing in...");
+        let tty = File::open("/dev/tty").map_err(|_| ShareError::NotSignedIn)?;
+        let mut signin = Command::new("op");
+        signin.arg("signin");
+        // v1 takes the account as an argument of `signin` rather than a flag
+        if let Some(account) = &self.account {
+            signin.arg(account);
+        }
+        let signin = crate::output::suspend(|| signin.stdin(tty).stderr(Stdio::inherit()).output())
+            .map_err(ShareError::OpUnavailable)?;
+        if !signin.status.success() {
+            return Err(ShareError::NotSignedIn);
+        }
+        if let Some(session) = session(&signin.stdout) {
+            let _ = self.session.set(session);
+        }
+        Ok(())
+    }
+
+    fn check_share_options(&self, _options: &ShareOptions) -> Result<()> {
+        Feature::ItemShare.require(Some(self.version))
+    }
+
+    fn list_vaults(&self) -> Result<Vec<Vault>> {
+        let vaults = self.json(self.command().arg("list").arg("vaults"), || {
+            "listing vaults".to_string()
+        })?;
+        Ok(vaults
+            .as_array()
+            .map(|vaults| vaults.iter().filter_map(Vault::from_json).collect())
+            .unwrap_or_default())
+    }
+
+    fn list_users(&self) -> Result<Vec<User>> {
+        let users = self.json(self.command().arg("list").arg("users"), || {
+            "listing users".to_string()
+        })?;
+        Ok(users
+            .as_array()
+            .map(|users| users.iter().filter_map(user).collect())
+            .unwrap_or_default())
+    }
+
+    fn ensure_vault(&self, vault: &str) -> Result<String> {
+        let vault_check = output(self.command().arg("get").arg("vault").arg(vault))?;
+        let vault_output = if vault_check.status.success() {
+            vault_check
+        } else {
+            crate::info!("Vault '{}' does not exist, creating it...", vault);
+            let vault_create = output(self.command().arg("create").arg("vault").arg(vault))?;
+            if !vault_create.status.success() {
+                return Err(ShareError::VaultCreate {
+                    vault: vault.to_string(),
+                    stderr: stderr(&vault_create),
+                });
+            }
+            vault_create
+        };
+
+        let vault_json: Value = serde_json::from_slice(&vault_output.stdout)?;
+        Ok(super::object_id(&vault_json).unwrap_or(vault).to_string())
+    }
+
+    fn grant_group(&self, _vault: &str, _group: &str, _permissions: &str) -> Result<()> {
+        Err(self.unsupported("--grant"))
+    }
+
+    fn get_template(&self, category: &str) -> Result<Value> {
+        // v1 templates have the v1 layout, so the v2 ones are built in instead
+        template::builtin(category).ok_or_else(|| ShareError::Template {
+            category: category.to_string(),
+            stderr: "There is no built-in template for it to use with op v1.".to_string(),
+        })
+    }
+
+    fn create_item(&self, title: &str, vault: &str, item: &Value) -> Result<String> {
+        let category = item
+            .get("category")
+            .and_then(Value::as_str)
+            .unwrap_or("SECURE_NOTE");
+        let tags: Vec<&str> = item
+            .get("tags")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+
+        // The details hold the secret, so they are read from a file only the user can read rather
+        // than passed encoded on the command line, where other users could see them
+        if self.version < TEMPLATE_FILE {
+            return Err(ShareError::OpTooOld {
+                feature: "Creating items".to_string(),
+                required: TEMPLATE_FILE.to_string(),
+                installed: self.version.to_string(),
+            });
+        }
+        let mut template = NamedTempFile::new()?;
+        serde_json::to_writer(&mut template, &details(item, category))?;
+        template.flush()?;
+
+        let mut create_command = self.command();
+        create_command
+            .arg("create")
+            .arg("item")
+            .arg(category_name(category))
+            .arg("--template")
+            .arg(template.path())
+            .arg("--title")
+            .arg(title)
+            .arg("--vault")
+            .arg(vault);
+        if !tags.is_empty() {
+            create_command.arg("--tags").arg(tags.join(","));
+        }
+
+        let item_create_output = output(&mut create_command)?;
+        if !item_create_output.status.success() {
+            return Err(ShareError::ItemCreate {
+                stderr: stderr(&item_create_output),
+            });
+        }
+        let item: Value = serde_json::from_slice(&item_create_output.stdout)?;
+        super::object_id(&item)
+            .map(str::to_string)
+            .ok_or(ShareError::MissingItemId)
+    }
+
+    fn create_document(
+        &self,
+        title: &str,
+        vault: &str,
+        file_name: &str,
+        content: &[u8],
+        tags: &[&str],
+    ) -> Result<String> {
+        // v1 reads documents from a file rather than stdin
+        let mut file = NamedTempFile::new()?;
+        file.write_all(content)?;
+        file.flush()?;
+
+        let mut create_command = self.command();
+        create_command
+            .arg("create")
+            .arg("document")
+            .arg(file.path())
+            .arg("--title")
+            .arg(title)
+            .arg("--vault")
+            .arg(vault)
+            .arg("--file-name")
+            .arg(file_name);
+        if !tags.is_empty() {
+            create_command.arg("--tags").arg(tags.join(","));
+        }
+
+        let document_create_output = output(&mut create_command)?;
+        if !document_create_output.status.success() {
+            return Err(ShareError::ItemCreate {
+                stderr: stderr(&document_create_output),
+            });
+        }
+        let document: Value = serde_json::from_slice(&document_create_output.stdout)?;
+        super::object_id(&document)
+            .map(str::to_string)
+            .ok_or(ShareError::MissingItemId)
+    }
+
+    fn list_items(&self, vault: &str, tags: &[&str]) -> Result<Vec<Value>> {
+        let mut list_command = self.command();
+        list_command
+            .arg("list")
+            .arg("items")
+            .arg("--vault")
+            .arg(vault);
+        if !tags.is_empty() {
+            list_command.arg("--tags").arg(tags.join(","));
+        }
+        let items = self.json(&mut list_command, || {
+            format!("listing items in vault '{}'", vault)
+        })?;
+        Ok(items
+            .as_array()
+            .map(|items| items.iter().map(item).collect())
+            .unwrap_or_default())
+    }
+
+    fn get_item(&self, item_id: &str, vault: &str) -> Result<Value> {
+        let json = self.json(
+            self.command()
+                .arg("get")
+                .arg("item")
+                .arg(item_id)
+                .arg("--vault")
+                .arg(vault),
+            || format!("getting item '{}'", item_id),
+        )?;
+        Ok(item(&json))
+    }
+
+    fn set_item_field(
+        &self,
+        _item_id: &str,
+        _vault: &str,
+        _field_id: &str,
+        _value: &str,
+    ) -> Result<()> {
+        Err(self.unsupported("Editing items"))
+    }
+
+    fn delete_item(&self, item_id: &str, vault: &str, mode: DeleteMode) -> Result<()> {
+        let mut delete_command = self.command();
+        delete_command
+            .arg("delete")
+            .arg("item")
+            .arg(item_id)
+            .arg("--vault")
+            .arg(vault);
+        if mode == DeleteMode::Archive {
+            delete_command.arg("--archive");
+        }
+
+        let delete_output = output(&mut delete_command)?;
+        if !delete_output.status.success() {
+            return Err(ShareError::Op {
+                action: format!("deleting item '{}'", item_id),
+                stderr: stderr(&delete_output),
+            });
+        }
+        Ok(())
+    }
+
+    fn share_item(&self, _item_id: &str, _vault: &str, options: &ShareOptions) -> Result<String> {
+        self.check_share_options(options)?;
+        Err(self.unsupported(Feature::ItemShare.name()))
+    }
+}
+
+/// A v1 user, which has a first and last name instead of a name.
+fn user(user: &Value) -> Option<User> {
+    let name = ["firstName", "lastName"]
+        .iter()
+        .filter_map(|key| user.get(key).and_then(Value::as_str))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    User::from_json(&json!({
+        "id": super::object_id(user)?,
+        "name": user.get("name").and_then(Value::as_str).unwrap_or(&name),
+        "email": user.get("email")?,
+    }))
+}
+
+/// A v1 item, with its title and tags in `overview` and its fields in `details`, in the v2
+/// layout.
+fn item(item: &Value) -> Value {
+    let overview = item.get("overview");
+    let details = item.get("details");
+    let mut fields = Vec::new();
+
+    if let Some(notes) = details.and_then(|details| details.get("notesPlain")) {
+        fields.push(json!({
+            "id": "notesPlain",
+            "type": "STRING",
+            "purpose": "NOTES",
+            "label": "notesPlain",
+            "value": notes,
+        }));
+    }
+    // The password of a Password item
+    if let Some(password) = details.and_then(|details| details.get("password")) {
+        fields.push(json!({
+            "id": "password",
+            "type": "CONCEALED",
+            "purpose": "PASSWORD",
+            "label": "password",
+            "value": password,
+        }));
+    }
+    // Login fields, marked by their designation
+    for field in details
+        .and_then(|details| details.get("fields"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let designation = field.get("designation").and_then(Value::as_str);
+        let name = field
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        fields.push(json!({
+            "id": designation.unwrap_or(name),
+            "type": if field.get("type").and_then(Value::as_str) == Some("P") { "CONCEALED" } else { "STRING" },
+            "purpose": designation.map(str::to_uppercase),
+            "label": name,
+            "value": field.get("value"),
+        }));
+    }
+    for section in details
+        .and_then(|details| details.get("sections"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        for field in section
+            .get("fields")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let kind = match field.get("k").and_then(Value::as_str) {
+                Some("concealed") => "CONCEALED",
+                Some("URL") => "URL",
+                Some("email") => "EMAIL",
+                _ => "STRING",
+            };
+            fields.push(json!({
+                "id": field.get("n"),
+                "type": kind,
+                "label": field.get("t"),
+                "value": field.get("v"),
+                "section": {
+                    "id": section.get("name"),
+                    "label": section.get("title"),
+                },
+            }));
+        }
+    }
+
+    json!({
+        "id": super::object_id(item),
+        "title": overview.and_then(|overview| overview.get("title")),
+        "tags": overview.and_then(|overview| overview.get("tags")),
+        "created_at": item.get("createdAt"),
+        "updated_at": item.get("updatedAt"),
+        "vault": { "id": item.get("vaultUuid") },
+        "fields": fields,
+    })
+}
+
+/// The v1 name of a v2 `category`, e.g. `"Secure Note"` for `SECURE_NOTE`.
+fn category_name(category: &str) -> String {
+    match category {
+        "API_CREDENTIAL" => "API Credential".to_string(),
+        _ => category
+            .split('_')
+            .map(|word| {
+                let word = word.to_lowercase();
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<String>>()
+            .join(" "),
+    }
+}
+
+/// The v1 `details` of a v2 `item` of `category`: the notes and a Password item's password at
+/// the top, login fields marked by their designation, and every other field in its section.
+fn details(item: &Value, category: &str) -> Value {
+    let mut details = Map::new();
+    let mut login_fields = Vec::new();
+    let mut sections: Vec<(Value, Value, Vec<Value>)> = Vec::new();
+
+    for field in item
+        .get("fields")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let text = |key: &str| field.get(key).and_then(Value::as_str).unwrap_or_default();
+        let value = field.get("value").cloned().unwrap_or(Value::from(""));
+        match (text("purpose"), category) {
+            ("NOTES", _) => {
+                details.insert("notesPlain".to_string(), value);
+                continue;
+            }
+            ("PASSWORD", "PASSWORD") => {
+                details.insert("password".to_string(), value);
+                continue;
+            }
+            ("USERNAME" | "PASSWORD", "LOGIN") => {
+                login_fields.push(json!({
+                    "designation": text("purpose").to_lowercase(),
+                    "name": text("id"),
+                    "type": if text("type") == "CONCEALED" { "P" } else { "T" },
+                    "value": value,
+                }));
+                continue;
+            }
+            _ => {}
+        }
+
+        let kind = match text("type") {
+            "CONCEALED" => "concealed",
+            "URL" => "URL",
+            "EMAIL" => "email",
+            _ => "string",
+        };
+        let section = field.get("section");
+        let id = section
+            .and_then(|section| section.get("id"))
+            .cloned()
+            .unwrap_or(Value::from(""));
+        let label = section
+            .and_then(|section| section.get("label"))
+            .cloned()
+            .unwrap_or(Value::from(""));
+        let field = json!({ "k": kind, "n": text("id"), "t": text("label"), "v": value });
+        match sections.iter_mut().find(|(name, _, _)| *name == id) {
+            Some((_, _, fields)) => fields.push(field),
+            None => sections.push((id, label, vec![field])),
+        }
+    }
+
+    if !login_fields.is_empty() {
+        details.insert("fields".to_string(), Value::from(login_fields));
+    }
+    details.insert(
+        "sections".to_string(),
+        sections
+            .into_iter()
+            .map(|(name, title, fields)| json!({ "name": name, "title": title, "fields": fields }))
+            .collect(),
+    );
+    Value::Object(details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_name_is_the_v1_name() {
+        assert_eq!(category_name("SECURE_NOTE"), "Secure Note");
+        assert_eq!(category_name("PASSWORD"), "Password");
+        assert_eq!(category_name("API_CREDENTIAL"), "API Credential");
+    }
+
+    #[test]
+    fn details_put_notes_and_login_fields_at_the_top() {
+        let item = json!({ "fields": [
+            { "id": "notesPlain", "type": "STRING", "purpose": "NOTES", "value": "hello" },
+            { "id": "username", "type": "STRING", "purpose": "USERNAME", "value": "anna" },
+            { "id": "password", "type": "CONCEALED", "purpose": "PASSWORD", "value": "hunter2" },
+        ]});
+        assert_eq!(
+            details(&item, "LOGIN"),
+            json!({
+                "notesPlain": "hello",
+                "fields": [
+                    { "designation": "username", "name": "username", "type": "T", "value": "anna" },
+                    { "designation": "password", "name": "password", "type": "P", "value": "hunter2" },
+                ],
+                "sections": [],
+            })
+        );
+    }
+
+    #[test]
+    fn details_group_other_fields_by_section() {
+        let item = json!({ "fields": [
+            { "id": "expires_at", "type": "STRING", "label": "share link expires", "value": "2026-01-01T00:00:00Z" },
+            {
+                "id": "DB_PASSWORD",
+                "type": "CONCEALED",
+                "label": "DB_PASSWORD",
+                "value": "hunter2",
+                "section": { "id": "database", "label": "Database" },
+            },
+        ]});
+        assert_eq!(
+            details(&item, "SECURE_NOTE")["sections"],
+            json!([
+                {
+                    "name": "",
+                    "title": "",
+                    "fields": [{ "k": "string", "n": "expires_at", "t": "share link expires", "v": "2026-01-01T00:00:00Z" }],
+                },
+                {
+                    "name": "database",
+                    "title": "Database",
+                    "fields": [{ "k": "concealed", "n": "DB_PASSWORD", "t": "DB_PASSWORD", "v": "hunter2" }],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn item_reads_back_what_details_wrote() {
+        let fields = json!([
+            { "id": "notesPlain", "type": "STRING", "purpose": "NOTES", "label": "notesPlain", "value": "hello" },
+            { "id": "password", "type": "CONCEALED", "purpose": "PASSWORD", "label": "password", "value": "hunter2" },
+            {
+                "id": "url",
+                "type": "URL",
+                "label": "website",
+                "value": "https://acme.com",
+                "section": { "id": "links", "label": "Links" },
+            },
+        ]);
+        let v1 = json!({
+            "uuid": "abc123",
+            "vaultUuid": "vault1",
+            "overview": { "title": "handoff", "tags": ["share-1password"] },
+            "details": details(&json!({ "fields": fields }), "PASSWORD"),
+        });
+        let v2 = item(&v1);
+        assert_eq!(v2["id"], "abc123");
+        assert_eq!(v2["title"], "handoff");
+        assert_eq!(v2["vault"]["id"], "vault1");
+        assert_eq!(v2["fields"], fields);
+    }
+}
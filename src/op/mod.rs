@@ -0,0 +1,174 @@
+//! Access to 1Password, abstracted over the mechanism used to reach it.
+
+pub mod cli;
+pub mod connect;
+pub mod dry_run;
+pub mod legacy;
+pub mod version;
+
+pub use cli::CliBackend;
+pub use connect::ConnectBackend;
+pub use dry_run::DryRunBackend;
+pub use legacy::LegacyCliBackend;
+pub use version::{Feature, OpVersion};
+
+use crate::error::Result;
+use serde_json::Value;
+
+/// A vault the backend can access.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vault {
+    pub id: String,
+    pub name: String,
+}
+
+impl Vault {
+    /// Parse a vault object as returned by `op vault list --format=json` or the Connect API.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            id: object_id(value)?.to_string(),
+            name: value.get("name")?.as_str()?.to_string(),
+        })
+    }
+}
+
+/// A member of the 1Password account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+}
+
+impl User {
+    /// Parse a user object as returned by `op user list --format=json`.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            id: object_id(value)?.to_string(),
+            name: value
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            email: value.get("email")?.as_str()?.to_string(),
+        })
+    }
+}
+
+/// An account the CLI is set up for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub id: String,
+    /// The sign-in address, e.g. `my.1password.com`.
+    pub url: String,
+    pub email: String,
+}
+
+impl Account {
+    /// Parse an account object as returned by `op account list --format=json`.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let text = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        Some(Self {
+            id: value.get("account_uuid")?.as_str()?.to_string(),
+            url: text("url"),
+            email: text("email"),
+        })
+    }
+}
+
+/// What happens to a deleted item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteMode {
+    /// Move the item to the archive, from where it can be restored.
+    Archive,
+    /// Delete the item for good.
+    Permanent,
+}
+
+/// How a share link should be generated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShareOptions {
+    /// Expiration time for the link, in `op item share --expires-in` format.
+    pub expires_in: String,
+    /// Email addresses to restrict the link to. Empty means anyone with the link.
+    pub emails: Vec<String>,
+    /// Whether the link stops working once it has been viewed.
+    pub view_once: bool,
+}
+
+/// The 1Password operations the share flow relies on.
+///
+/// Backends are shared between threads when several items are created at once.
+pub trait OpBackend: Send + Sync {
+    /// Check that the backend is authenticated. Backends without a session concept succeed.
+    fn ensure_signed_in(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Check that links with `options` can be generated, before anything is created. Backends
+    /// supporting every option succeed.
+    fn check_share_options(&self, _options: &ShareOptions) -> Result<()> {
+        Ok(())
+    }
+
+    /// List the vaults the backend can access.
+    fn list_vaults(&self) -> Result<Vec<Vault>>;
+
+    /// List the members of the account.
+    fn list_users(&self) -> Result<Vec<User>>;
+
+    /// Make sure `vault` exists, creating it if needed, and return its ID.
+    fn ensure_vault(&self, vault: &str) -> Result<String>;
+
+    /// Give `group` the comma-separated `op` `permissions` in `vault`.
+    fn grant_group(&self, vault: &str, group: &str, permissions: &str) -> Result<()>;
+
+    /// Fetch the item template for `category`, e.g. `"Secure Note"`.
+    fn get_template(&self, category: &str) -> Result<Value>;
+
+    /// Create an item titled `title` in `vault` from a filled-in template, returning its ID.
+    ///
+    /// Tags are taken from the item's `tags` array.
+    fn create_item(&self, title: &str, vault: &str, item: &Value) -> Result<String>;
+
+    /// Upload `content` as a document named `file_name` in `vault`, returning its ID.
+    fn create_document(
+        &self,
+        title: &str,
+        vault: &str,
+        file_name: &str,
+        content: &[u8],
+        tags: &[&str],
+    ) -> Result<String>;
+
+    /// List summaries of the items in `vault` carrying all of `tags`.
+    fn list_items(&self, vault: &str, tags: &[&str]) -> Result<Vec<Value>>;
+
+    /// Fetch an item with all its fields.
+    fn get_item(&self, item_id: &str, vault: &str) -> Result<Value>;
+
+    /// Set the value of a text field on an existing item, adding the field if it is missing.
+    fn set_item_field(&self, item_id: &str, vault: &str, field_id: &str, value: &str)
+        -> Result<()>;
+
+    /// Delete an item. Share links for it stop working.
+    fn delete_item(&self, item_id: &str, vault: &str, mode: DeleteMode) -> Result<()>;
+
+    /// Generate a share link for an existing item.
+    fn share_item(&self, item_id: &str, vault: &str, options: &ShareOptions) -> Result<String>;
+}
+
+/// Extract the `id` (or legacy `uuid`) of an object returned by 1Password.
+pub fn object_id(value: &Value) -> Option<&str> {
+    value
+        .get("id")
+        .or(value.get("uuid"))
+        .and_then(|id| id.as_str())
+        .filter(|id| !id.is_empty())
+}
@@ -0,0 +1,85 @@
+//! The version of the installed `op` binary, and the features that need a newer one.
+
+use crate::error::{Result, ShareError};
+use std::fmt;
+
+/// A `MAJOR.MINOR.PATCH` version of the 1Password CLI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OpVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl OpVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse the output of `op --version`, e.g. `2.24.0` or `2.25.0-beta.01`.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim();
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let release = version.split(['-', '+']).next()?;
+        let mut parts = release.split('.').map(|part| part.parse().ok());
+        Some(Self {
+            major: parts.next()??,
+            minor: parts.next().unwrap_or(Some(0))?,
+            patch: parts.next().unwrap_or(Some(0))?,
+        })
+    }
+}
+
+impl fmt::Display for OpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// What this tool uses that older `op` releases lack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// `op item share`, to generate share links.
+    ItemShare,
+    /// `op item share --emails`, to restrict links to recipients.
+    ShareEmails,
+    /// `op item share --view-once`, for links that work for a single view.
+    ViewOnce,
+}
+
+impl Feature {
+    /// How the feature is referred to in errors.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::ItemShare => "Sharing items",
+            Feature::ShareEmails => "--emails",
+            Feature::ViewOnce => "--view-once",
+        }
+    }
+
+    /// The first `op` release supporting the feature.
+    pub fn min_version(self) -> OpVersion {
+        match self {
+            Feature::ItemShare => OpVersion::new(2, 5, 0),
+            Feature::ShareEmails => OpVersion::new(2, 6, 0),
+            Feature::ViewOnce => OpVersion::new(2, 10, 0),
+        }
+    }
+
+    /// Fail with [`ShareError::OpTooOld`] if `installed` is too old for the feature. An unknown
+    /// version is given the benefit of the doubt.
+    pub fn require(self, installed: Option<OpVersion>) -> Result<()> {
+        match installed {
+            Some(installed) if installed < self.min_version() => Err(ShareError::OpTooOld {
+                feature: self.name().to_string(),
+                required: self.min_version().to_string(),
+                installed: installed.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Where messages go: results on stdout, everything meant for a person on stderr.
+//!
+//! Keeping stdout to the bare result lets it be piped or captured with `$(...)`. Informational
+//! messages are printed with [`info!`](crate::info), which stays silent once [`set_quiet`] has
+//! been called; warnings and errors are always printed.
+//!
+//! Inside GitHub Actions, results can instead be handed to later steps through the step's output
+//! file, with the links masked in the log.
+//!
+//! In CI, or without a terminal or display, the run is [unattended](is_unattended): nothing is
+//! asked, copied, or printed beyond the result, warnings, and errors.
+//!
+//! While the `op` calls of a share run, the current [step] is shown on a spinner on stderr.
+//!
+//! Messages are colored by their [`Style`] on streams that are terminals, unless `NO_COLOR` is
+//! set or [`set_color`] says otherwise. In [plain](set_plain) mode there are no colors, symbols,
+//! or spinners, and each message is a single line, for screen readers and log collectors.
+
+use crate::error::{Result, ShareError};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fmt::Display;
+use std::fs::{File, OpenOptions};
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static UNATTENDED: AtomicBool = AtomicBool::new(false);
+static PLAIN: AtomicBool = AtomicBool::new(false);
+static SPINNER: Mutex<Option<ProgressBar>> = Mutex::new(None);
+static COLOR_STDOUT: AtomicBool = AtomicBool::new(false);
+static COLOR_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Suppress informational messages for the rest of the process.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether informational messages are suppressed.
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Print without colors, symbols, or spinners for the rest of the process.
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+/// Whether output is plain: uncolored, without symbols or spinners, one line per message.
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// Treat the rest of the process as running without anyone watching it.
+pub fn set_unattended(unattended: bool) {
+    UNATTENDED.store(unattended, Ordering::Relaxed);
+}
+
+/// Whether the process runs without anyone watching it, so it behaves as if `--quiet`,
+/// `--no-clipboard`, and `--yes` were given.
+pub fn is_unattended() -> bool {
+    UNATTENDED.load(Ordering::Relaxed)
+}
+
+/// Whether this looks like an unattended run: in CI (`CI` is set, other than to `false` or `0`),
+/// or with neither a terminal nor a display.
+pub fn detect_unattended() -> bool {
+    let ci = std::env::var("CI").is_ok_and(|ci| !matches!(ci.trim(), "" | "0" | "false"));
+    let no_terminal = !std::io::stderr().is_terminal() && File::open("/dev/tty").is_err();
+    ci || (no_terminal && crate::clipboard::is_headless())
+}
+
+/// Print an informational message to stderr, unless [`set_quiet`](crate::output::set_quiet)
+/// suppressed them.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            $crate::output::suspend(|| eprintln!($($arg)*));
+        }
+    };
+}
+
+/// Print a warning to stderr, after a highlighted "Warning:".
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        $crate::output::suspend(|| {
+            eprintln!(
+                "{} {}",
+                $crate::output::Style::Warning.stderr("Warning:"),
+                format_args!($($arg)*)
+            )
+        })
+    };
+}
+
+/// When output is colored, from `--color`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// On terminals, unless `NO_COLOR` is set or the run is [unattended](is_unattended).
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Decide whether stdout and stderr are colored for the rest of the process.
+pub fn set_color(choice: ColorChoice) {
+    let enabled = |terminal: bool| match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            terminal
+                && !is_unattended()
+                && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        }
+    };
+    COLOR_STDOUT.store(enabled(std::io::stdout().is_terminal()), Ordering::Relaxed);
+    COLOR_STDERR.store(enabled(std::io::stderr().is_terminal()), Ordering::Relaxed);
+}
+
+/// What a piece of output is, which decides its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Something went as asked, in green.
+    Success,
+    /// Something needs attention, in yellow.
+    Warning,
+    /// Something failed, in bold red.
+    Error,
+    /// A share link, in bold cyan.
+    Link,
+    /// When a link expires, in yellow.
+    Expiry,
+}
+
+impl Style {
+    /// `text` styled for stdout.
+    pub fn stdout(self, text: impl Display) -> String {
+        self.paint(text, COLOR_STDOUT.load(Ordering::Relaxed))
+    }
+
+    /// `text` styled for stderr.
+    pub fn stderr(self, text: impl Display) -> String {
+        self.paint(text, COLOR_STDERR.load(Ordering::Relaxed))
+    }
+
+    fn paint(self, text: impl Display, enabled: bool) -> String {
+        if !enabled || is_plain() {
+            return text.to_string();
+        }
+        let code = match self {
+            Style::Success => "32",
+            Style::Warning | Style::Expiry => "33",
+            Style::Error => "1;31",
+            Style::Link => "1;36",
+        };
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    }
+}
+
+/// Show `label`, e.g. "Creating item", as the step a share is at, on a spinner on stderr.
+///
+/// The spinner is only shown on a terminal, and not when informational messages are suppressed
+/// or output is plain.
+pub fn step(label: &str) {
+    if is_quiet() || is_plain() || !std::io::stderr().is_terminal() {
+        return;
+    }
+    let mut spinner = SPINNER.lock().unwrap_or_else(|err| err.into_inner());
+    let spinner = spinner.get_or_insert_with(|| {
+        let spinner = ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template("{spinner} {msg}")
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        spinner.enable_steady_tick(Duration::from_millis(100));
+        spinner
+    });
+    spinner.set_message(format!("{}…", label));
+}
+
+/// Removes the spinner when dropped, at the end of the operation whose [`step`]s it shows.
+pub struct Steps;
+
+impl Drop for Steps {
+    fn drop(&mut self) {
+        let spinner = SPINNER.lock().unwrap_or_else(|err| err.into_inner()).take();
+        if let Some(spinner) = spinner {
+            spinner.finish_and_clear();
+        }
+    }
+}
+
+/// Run `f` with the spinner hidden, so what it prints or asks on the terminal is not drawn over.
+pub fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    let spinner = SPINNER
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone();
+    match spinner {
+        Some(spinner) => spinner.suspend(f),
+        None => f(),
+    }
+}
+
+/// The environment variable GitHub Actions passes the path of a step's output file in.
+pub const GITHUB_OUTPUT_ENV: &str = "GITHUB_OUTPUT";
+
+/// The workflow command that hides `value` in the rest of the GitHub Actions log.
+pub fn github_mask(value: &str) -> String {
+    format!("::add-mask::{}", github_escape(value))
+}
+
+/// The workflow command that reports `message` as an error annotation of the step.
+pub fn github_error(message: &str) -> String {
+    format!("::error::{}", github_escape(message))
+}
+
+/// Append `outputs` to the step's output file, so later steps can read them as
+/// `steps.<id>.outputs.<name>`.
+pub fn set_github_outputs(outputs: &[(&str, &str)]) -> Result<()> {
+    let path = std::env::var_os(GITHUB_OUTPUT_ENV).ok_or(ShareError::GithubOutput)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for (name, value) in outputs {
+        if value.contains('\n') {
+            writeln!(file, "{}<<{}\n{}\n{}", name, DELIMITER, value, DELIMITER)?;
+        } else {
+            writeln!(file, "{}={}", name, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Ends a multiline value in the output file; links and item IDs cannot contain it.
+const DELIMITER: &str = "SHARE_1PASSWORD_EOF";
+
+/// Workflow commands end at a line break, so those in their values are percent-encoded.
+fn github_escape(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
@@ -0,0 +1,50 @@
+//! Detection of PEM-encoded keys and certificates in the shared content.
+
+/// A `-----BEGIN ...-----` to `-----END ...-----` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PemBlock {
+    /// What the block holds, e.g. `RSA PRIVATE KEY` or `CERTIFICATE`.
+    pub label: String,
+    /// The whole block, including its BEGIN and END lines.
+    pub text: String,
+}
+
+/// The PEM blocks in `content`, and the content with them removed.
+pub fn extract(content: &str) -> (Vec<PemBlock>, String) {
+    let mut blocks = Vec::new();
+    let mut rest = String::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some((label, lines)) = current.as_mut() {
+            lines.push(trimmed);
+            if trimmed == format!("-----END {}-----", label) {
+                let (label, lines) = current.take().expect("inside a block");
+                blocks.push(PemBlock {
+                    label,
+                    text: lines.join("\n"),
+                });
+            }
+            continue;
+        }
+        if let Some(label) = trimmed
+            .strip_prefix("-----BEGIN ")
+            .and_then(|rest| rest.strip_suffix("-----"))
+        {
+            current = Some((label.to_string(), vec![trimmed]));
+            continue;
+        }
+        rest.push_str(line);
+        rest.push('\n');
+    }
+
+    // An unterminated block is not PEM, so it stays in the content
+    if let Some((_, lines)) = current {
+        for line in lines {
+            rest.push_str(line);
+            rest.push('\n');
+        }
+    }
+    (blocks, rest)
+}
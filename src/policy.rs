@@ -0,0 +1,271 @@
+//! Organization rules on what may be shared, and how.
+//!
+//! A policy is a TOML file with one table per rule. Every rule has a `severity`: `deny` (the
+//! default) refuses the share, `warn` only reports it.
+//!
+//! ```toml
+//! [deny_keys]
+//! patterns = ["ROOT_*", "*_PRIVATE_KEY"]
+//!
+//! [max_expires_in]
+//! value = "7d"
+//! severity = "warn"
+//!
+//! [require_recipients]
+//!
+//! [allowed_vaults]
+//! vaults = ["Shared Notes", "Client Handoffs"]
+//! ```
+
+use crate::error::{Result, ShareError};
+use crate::expiry;
+use crate::sections::glob_match;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Whether breaking a rule refuses the share or only warns about it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Deny,
+    Warn,
+}
+
+/// A parsed policy file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    /// Variables whose keys may never be shared.
+    pub deny_keys: Option<DenyKeys>,
+    /// The longest a share link may stay valid.
+    pub max_expires_in: Option<MaxExpiresIn>,
+    /// Share links must be restricted to recipients.
+    pub require_recipients: Option<RequireRecipients>,
+    /// The only vaults items may be stored in.
+    pub allowed_vaults: Option<AllowedVaults>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DenyKeys {
+    /// Glob patterns on variable keys.
+    pub patterns: Vec<String>,
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaxExpiresIn {
+    /// A duration such as `7d`.
+    pub value: String,
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RequireRecipients {
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AllowedVaults {
+    /// Vault names, compared without regard to case.
+    pub vaults: Vec<String>,
+    pub severity: Severity,
+}
+
+/// What a share is about to do, as far as the policy is concerned.
+#[derive(Debug, Clone, Copy)]
+pub struct Request<'a> {
+    pub vault: &'a str,
+    pub expires_in: &'a str,
+    pub recipients: &'a [String],
+    /// The keys of the shared variables. Empty for content that is not a set of variables.
+    pub keys: &'a [String],
+}
+
+/// A rule the share breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The name of the rule in the policy file.
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Policy {
+    /// Read the policy at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let error = |message: String| ShareError::Policy {
+            path: path.to_path_buf(),
+            message,
+        };
+        let contents = std::fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+        let policy: Policy = toml::from_str(&contents).map_err(|err| error(err.to_string()))?;
+        if let Some(rule) = &policy.max_expires_in {
+            if expiry::parse(&rule.value).is_none() {
+                return Err(error(format!(
+                    "max_expires_in: invalid duration '{}'",
+                    rule.value
+                )));
+            }
+        }
+        Ok(policy)
+    }
+
+    /// The rules `request` breaks.
+    pub fn evaluate(&self, request: &Request) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        if let Some(rule) = &self.deny_keys {
+            let denied: Vec<&str> = request
+                .keys
+                .iter()
+                .filter(|key| rule.patterns.iter().any(|pattern| glob_match(pattern, key)))
+                .map(String::as_str)
+                .collect();
+            if !denied.is_empty() {
+                violations.push(Violation {
+                    rule: "deny_keys",
+                    severity: rule.severity,
+                    message: format!("{} may not be shared", denied.join(", ")),
+                });
+            }
+        }
+
+        if let Some(rule) = &self.max_expires_in {
+            let longer = match (
+                expiry::parse(request.expires_in),
+                expiry::parse(&rule.value),
+            ) {
+                (Some(requested), Some(max)) => requested > max,
+                // An expiry that cannot be interpreted cannot be shown to be short enough
+                (None, _) => true,
+                (_, None) => false,
+            };
+            if longer {
+                violations.push(Violation {
+                    rule: "max_expires_in",
+                    severity: rule.severity,
+                    message: format!(
+                        "links may stay valid for at most {}, not {}",
+                        rule.value, request.expires_in
+                    ),
+                });
+            }
+        }
+
+        if let Some(rule) = &self.require_recipients {
+            if request.recipients.is_empty() {
+                violations.push(Violation {
+                    rule: "require_recipients",
+                    severity: rule.severity,
+                    message: "links must be restricted to recipients with --emails".to_string(),
+                });
+            }
+        }
+
+        if let Some(rule) = &self.allowed_vaults {
+            if !rule
+                .vaults
+                .iter()
+                .any(|vault| vault.eq_ignore_ascii_case(request.vault))
+            {
+                violations.push(Violation {
+                    rule: "allowed_vaults",
+                    severity: rule.severity,
+                    message: format!(
+                        "vault '{}' is not one of {}",
+                        request.vault,
+                        rule.vaults.join(", ")
+                    ),
+                });
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request<'a>(recipients: &'a [String], keys: &'a [String]) -> Request<'a> {
+        Request {
+            vault: "Shared Notes",
+            expires_in: "7d",
+            recipients,
+            keys,
+        }
+    }
+
+    fn rules(violations: &[Violation]) -> Vec<(&'static str, Severity)> {
+        violations
+            .iter()
+            .map(|violation| (violation.rule, violation.severity))
+            .collect()
+    }
+
+    #[test]
+    fn an_empty_policy_allows_everything() {
+        assert!(Policy::default().evaluate(&request(&[], &[])).is_empty());
+    }
+
+    #[test]
+    fn evaluate_reports_every_broken_rule() {
+        let policy: Policy = toml::from_str(
+            "[deny_keys]\npatterns = [\"ROOT_*\"]\n\
+             [max_expires_in]\nvalue = \"1d\"\nseverity = \"warn\"\n\
+             [require_recipients]\n\
+             [allowed_vaults]\nvaults = [\"Client Handoffs\"]\n",
+        )
+        .unwrap();
+        let keys = ["ROOT_PASSWORD".to_string(), "API_KEY".to_string()];
+        let violations = policy.evaluate(&request(&[], &keys));
+        assert_eq!(
+            rules(&violations),
+            [
+                ("deny_keys", Severity::Deny),
+                ("max_expires_in", Severity::Warn),
+                ("require_recipients", Severity::Deny),
+                ("allowed_vaults", Severity::Deny),
+            ]
+        );
+        assert_eq!(violations[0].message, "ROOT_PASSWORD may not be shared");
+    }
+
+    #[test]
+    fn evaluate_allows_a_share_within_the_rules() {
+        let policy: Policy = toml::from_str(
+            "[deny_keys]\npatterns = [\"ROOT_*\"]\n\
+             [max_expires_in]\nvalue = \"1w\"\n\
+             [require_recipients]\n\
+             [allowed_vaults]\nvaults = [\"shared notes\"]\n",
+        )
+        .unwrap();
+        let recipients = ["anna@acme.com".to_string()];
+        let keys = ["API_KEY".to_string()];
+        assert!(policy.evaluate(&request(&recipients, &keys)).is_empty());
+    }
+
+    #[test]
+    fn an_unknown_expiry_breaks_max_expires_in() {
+        let policy = Policy {
+            max_expires_in: Some(MaxExpiresIn {
+                value: "7d".to_string(),
+                severity: Severity::Deny,
+            }),
+            ..Policy::default()
+        };
+        let request = Request {
+            expires_in: "forever",
+            ..request(&[], &[])
+        };
+        assert_eq!(
+            rules(&policy.evaluate(&request)),
+            [("max_expires_in", Severity::Deny)]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Presets that read commonly shared credentials from their usual place on disk and map them
+//! onto the right item category and fields.
+
+use crate::config;
+use crate::error::{Result, ShareError};
+use crate::template::{Category, Field, FieldType};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the location of the AWS credentials file.
+pub const AWS_CREDENTIALS_ENV: &str = "AWS_SHARED_CREDENTIALS_FILE";
+
+/// Environment variable listing the kubeconfig files.
+pub const KUBECONFIG_ENV: &str = "KUBECONFIG";
+
+/// The AWS profile used when none is chosen.
+pub const AWS_DEFAULT_PROFILE: &str = "default";
+
+/// An item prepared by a preset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetItem {
+    pub category: Category,
+    /// A title describing the item, used unless another one is chosen.
+    pub title: String,
+    /// The content stored in the category's main field.
+    pub content: String,
+    /// Fields set in addition to the main one.
+    pub fields: Vec<Field>,
+    /// The file name of a document item.
+    pub file_name: Option<String>,
+}
+
+/// The access key of `profile` in the AWS credentials file, as an API Credential item.
+///
+/// The access key ID becomes the username, the secret access key the credential, and a session
+/// token and region are added when the profile has them.
+pub fn aws(profile: Option<&str>) -> Result<PresetItem> {
+    let profile = profile.unwrap_or(AWS_DEFAULT_PROFILE);
+    let path = std::env::var_os(AWS_CREDENTIALS_ENV)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| config::home_dir().map(|home| home.join(".aws").join("credentials")))
+        .ok_or_else(|| error("aws", "cannot locate the AWS credentials file"))?;
+    let contents = read(&path, "aws")?;
+
+    let entries = ini_section(&contents, profile).ok_or_else(|| {
+        error(
+            "aws",
+            format!("no profile '{}' in {}", profile, path.display()),
+        )
+    })?;
+    let get = |key: &str| {
+        entries
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.clone())
+    };
+    let (Some(access_key_id), Some(secret_access_key)) =
+        (get("aws_access_key_id"), get("aws_secret_access_key"))
+    else {
+        return Err(error(
+            "aws",
+            format!(
+                "profile '{}' has no aws_access_key_id and aws_secret_access_key",
+                profile
+            ),
+        ));
+    };
+
+    let mut fields = vec![Field::new(
+        "username",
+        "access key id",
+        FieldType::Text,
+        access_key_id,
+    )];
+    if let Some(token) = get("aws_session_token") {
+        fields.push(Field::new(
+            "sessionToken",
+            "session token",
+            FieldType::Concealed,
+            token,
+        ));
+    }
+    if let Some(region) = get("region") {
+        fields.push(Field::new("region", "region", FieldType::Text, region));
+    }
+
+    Ok(PresetItem {
+        category: Category::ApiCredential,
+        title: format!("AWS credentials ({})", profile),
+        content: secret_access_key,
+        fields,
+        file_name: None,
+    })
+}
+
+/// The kubeconfig, or only what `context` needs from it, as a document.
+///
+/// Reads the first file listed in `KUBECONFIG`, falling back to `~/.kube/config`.
+pub fn kubeconfig(context: Option<&str>) -> Result<PresetItem> {
+    let path = std::env::var(KUBECONFIG_ENV)
+        .ok()
+        .and_then(|paths| std::env::split_paths(&paths).find(|path| !path.as_os_str().is_empty()))
+        .or_else(|| config::home_dir().map(|home| home.join(".kube").join("config")))
+        .ok_or_else(|| error("kubeconfig", "cannot locate the kubeconfig"))?;
+    let contents = read(&path, "kubeconfig")?;
+    let kubeconfig: Value = serde_yaml::from_str(&contents).map_err(|err| {
+        error(
+            "kubeconfig",
+            format!("invalid kubeconfig {}: {}", path.display(), err),
+        )
+    })?;
+
+    let Some(context) = context else {
+        let current = kubeconfig
+            .get("current-context")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let mut fields = Vec::new();
+        if let Some(current) = &current {
+            fields.push(Field::new("context", "context", FieldType::Text, current));
+        }
+        return Ok(PresetItem {
+            category: Category::Document,
+            title: "kubeconfig".to_string(),
+            content: contents,
+            fields,
+            file_name: Some("config".to_string()),
+        });
+    };
+
+    // Keep only the context and the cluster and user it refers to
+    let named = |list: &str, name: &str| {
+        kubeconfig
+            .get(list)
+            .and_then(Value::as_array)
+            .and_then(|entries| {
+                entries
+                    .iter()
+                    .find(|entry| entry.get("name").and_then(Value::as_str) == Some(name))
+            })
+            .cloned()
+    };
+    let entry = named("contexts", context).ok_or_else(|| {
+        error(
+            "kubeconfig",
+            format!("no context '{}' in {}", context, path.display()),
+        )
+    })?;
+    let reference = |key: &str| {
+        entry
+            .get("context")
+            .and_then(|context| context.get(key))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let cluster = reference("cluster").and_then(|name| named("clusters", &name));
+    let user = reference("user").and_then(|name| named("users", &name));
+    let server = cluster
+        .as_ref()
+        .and_then(|cluster| cluster.get("cluster")?.get("server")?.as_str())
+        .map(str::to_string);
+
+    let minimal = json!({
+        "apiVersion": "v1",
+        "kind": "Config",
+        "current-context": context,
+        "contexts": [entry],
+        "clusters": cluster.into_iter().collect::<Vec<_>>(),
+        "users": user.into_iter().collect::<Vec<_>>(),
+    });
+    let content =
+        serde_yaml::to_string(&minimal).map_err(|err| error("kubeconfig", err.to_string()))?;
+
+    let mut fields = vec![Field::new("context", "context", FieldType::Text, context)];
+    if let Some(server) = server {
+        fields.push(Field::new("server", "server", FieldType::Url, server));
+    }
+    Ok(PresetItem {
+        category: Category::Document,
+        title: format!("kubeconfig ({})", context),
+        content,
+        fields,
+        file_name: Some("config".to_string()),
+    })
+}
+
+/// The SSH client configuration in `~/.ssh/config`, as a document listing its hosts.
+pub fn ssh_config() -> Result<PresetItem> {
+    let path = config::home_dir()
+        .map(|home| home.join(".ssh").join("config"))
+        .ok_or_else(|| error("ssh-config", "cannot locate ~/.ssh/config"))?;
+    let content = read(&path, "ssh-config")?;
+
+    let hosts: Vec<&str> = content
+        .lines()
+        .filter_map(|line| {
+            let (keyword, value) = line.trim().split_once(char::is_whitespace)?;
+            keyword.eq_ignore_ascii_case("host").then(|| value.trim())
+        })
+        .flat_map(str::split_whitespace)
+        .filter(|host| !host.contains(['*', '?']))
+        .collect();
+    let mut fields = Vec::new();
+    if !hosts.is_empty() {
+        fields.push(Field::new(
+            "hosts",
+            "hosts",
+            FieldType::Text,
+            hosts.join(", "),
+        ));
+    }
+
+    Ok(PresetItem {
+        category: Category::Document,
+        title: "SSH config".to_string(),
+        content,
+        fields,
+        file_name: Some("config".to_string()),
+    })
+}
+
+fn read(path: &Path, preset: &str) -> Result<String> {
+    std::fs::read_to_string(path)
+        .map_err(|err| error(preset, format!("cannot read {}: {}", path.display(), err)))
+}
+
+fn error(preset: &str, message: impl Into<String>) -> ShareError {
+    ShareError::Preset {
+        preset: preset.to_string(),
+        message: message.into(),
+    }
+}
+
+/// The `key = value` entries of the `[name]` section of an INI file, if it has one.
+fn ini_section(contents: &str, name: &str) -> Option<Vec<(String, String)>> {
+    let mut entries = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if entries.is_some() {
+                break;
+            }
+            if section.trim() == name {
+                entries = Some(Vec::new());
+            }
+            continue;
+        }
+        if let (Some(entries), Some((key, value))) = (entries.as_mut(), line.split_once('=')) {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    entries
+}
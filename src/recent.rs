@@ -0,0 +1,71 @@
+//! The vault last picked in each project, stored as JSON in
+//! `$XDG_DATA_HOME/share-1password/recent-vaults.json` so the picker can start on it.
+
+use crate::error::Result;
+use crate::git;
+use crate::history;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+/// File name of the store of recently picked vaults.
+pub const RECENT_VAULTS_FILE: &str = "recent-vaults.json";
+
+/// The vaults last picked, by project directory.
+#[derive(Debug, Clone)]
+pub struct RecentVaults {
+    path: PathBuf,
+}
+
+impl RecentVaults {
+    /// The store at its default location, if a home directory is known.
+    pub fn open() -> Option<Self> {
+        history::data_dir().map(|dir| Self::at(dir.join(RECENT_VAULTS_FILE)))
+    }
+
+    /// A store at `path`.
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The vault last picked in the project containing `dir`.
+    pub fn get(&self, dir: &Path) -> Option<String> {
+        self.load()
+            .get(&project_key(dir))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    }
+
+    /// Remember `vault` as picked in the project containing `dir`.
+    pub fn set(&self, dir: &Path, vault: &str) -> Result<()> {
+        let mut vaults = self.load();
+        vaults.insert(project_key(dir), Value::String(vault.to_string()));
+
+        let parent = self.path.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(parent)?;
+        let tmp = NamedTempFile::new_in(parent)?;
+        serde_json::to_writer_pretty(&tmp, &vaults)?;
+        tmp.persist(&self.path).map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    /// The stored vaults, or none if the store is missing or unreadable.
+    fn load(&self) -> Map<String, Value> {
+        std::fs::read(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_slice(&contents).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// The project `dir` belongs to: the top-level directory of its git repository, or `dir` itself.
+fn project_key(dir: &Path) -> String {
+    git::toplevel(dir)
+        .unwrap_or_else(|| dir.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
@@ -0,0 +1,207 @@
+//! Turning the recipients given on the command line into email addresses.
+
+use crate::classify;
+use crate::error::{Result, ShareError};
+use crate::op::User;
+use std::path::Path;
+
+/// `recipients` as a single list: entries holding several recipients are [split](split),
+/// domains of email addresses are lowercased, and repeated recipients are dropped.
+pub fn normalize(recipients: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for recipient in recipients.iter().flat_map(|entry| split(entry)) {
+        let recipient = match recipient.rsplit_once('@') {
+            Some((local, domain)) => format!("{}@{}", local, domain.to_lowercase()),
+            None => recipient,
+        };
+        if !normalized
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(&recipient))
+        {
+            normalized.push(recipient);
+        }
+    }
+    normalized
+}
+
+/// The recipients listed in the file at `path`, one per line. Blank lines and `#` comments are
+/// ignored.
+pub fn read_file(path: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path).map_err(|err| ShareError::InputFile {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(entry, _)| entry))
+        .flat_map(split)
+        .collect())
+}
+
+/// Split a list of recipients separated by commas, dropping empty entries.
+///
+/// Whitespace separates the words of a name, such as `"Anna Smith"`, so it only separates
+/// recipients between email addresses, as in `"anna@acme.com bob@acme.com"`.
+pub fn split(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .flat_map(|entry| {
+            let words: Vec<&str> = entry.split_whitespace().collect();
+            if words.iter().all(|word| classify::is_email(word)) {
+                words.into_iter().map(str::to_string).collect()
+            } else {
+                vec![entry.to_string()]
+            }
+        })
+        .collect()
+}
+
+/// The email addresses of `recipients`, each either an email address, kept as it is, or a name,
+/// part of a name, or part of an email address matching a single member of the account.
+///
+/// The members are only listed, through `users`, if a recipient is not an email address.
+pub fn resolve(
+    recipients: &[String],
+    users: impl FnOnce() -> Result<Vec<User>>,
+) -> Result<Vec<String>> {
+    // Anything with an @ in it is meant as an address, not a name
+    if let Some(invalid) = recipients
+        .iter()
+        .find(|recipient| recipient.contains('@') && !classify::is_email(recipient))
+    {
+        return Err(ShareError::InvalidEmail {
+            email: invalid.clone(),
+        });
+    }
+    if recipients
+        .iter()
+        .all(|recipient| classify::is_email(recipient))
+    {
+        return Ok(recipients.to_vec());
+    }
+    let users = users()?;
+
+    let mut emails: Vec<String> = Vec::new();
+    for recipient in recipients {
+        let email = if classify::is_email(recipient) {
+            recipient.clone()
+        } else {
+            find(recipient, &users)?.email.clone()
+        };
+        if !emails.contains(&email) {
+            emails.push(email);
+        }
+    }
+    Ok(emails)
+}
+
+/// Refuse to share with addresses outside the `allowed` domains, or their subdomains. Any domain
+/// is allowed if none are listed.
+pub fn check_domains(emails: &[String], allowed: &[String]) -> Result<()> {
+    if allowed.is_empty() {
+        return Ok(());
+    }
+    for email in emails {
+        let domain = email.rsplit('@').next().unwrap_or_default().to_lowercase();
+        let allowed_domain = allowed.iter().any(|allowed| {
+            let allowed = allowed.trim_start_matches('@').to_lowercase();
+            domain == allowed || domain.ends_with(&format!(".{}", allowed))
+        });
+        if !allowed_domain {
+            return Err(ShareError::Blocked {
+                reason: format!(
+                    "{} is outside the allowed email domains ({})",
+                    email,
+                    allowed.join(", ")
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The member `recipient` refers to: the one whose name or email address up to the `@` it is, or
+/// else the only one whose name or email address contains it.
+fn find<'a>(recipient: &str, users: &'a [User]) -> Result<&'a User> {
+    let needle = recipient.to_lowercase();
+    let local_part = |user: &User| {
+        user.email
+            .split('@')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+
+    let exact: Vec<&User> = users
+        .iter()
+        .filter(|user| user.name.to_lowercase() == needle || local_part(user) == needle)
+        .collect();
+    let matches = if exact.is_empty() {
+        users
+            .iter()
+            .filter(|user| {
+                user.name.to_lowercase().contains(&needle)
+                    || user.email.to_lowercase().contains(&needle)
+            })
+            .collect()
+    } else {
+        exact
+    };
+
+    match matches.as_slice() {
+        [user] => Ok(user),
+        [] => Err(ShareError::UnknownRecipient {
+            recipient: recipient.to_string(),
+        }),
+        _ => Err(ShareError::AmbiguousRecipient {
+            recipient: recipient.to_string(),
+            matches: matches
+                .iter()
+                .map(|user| match user.name.as_str() {
+                    "" => user.email.clone(),
+                    name => format!("{} <{}>", name, user.email),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_keeps_names_whole() {
+        assert_eq!(split("Anna Smith"), ["Anna Smith"]);
+        assert_eq!(
+            split("Anna Smith, bob@acme.com"),
+            ["Anna Smith", "bob@acme.com"]
+        );
+    }
+
+    #[test]
+    fn split_separates_addresses_on_whitespace() {
+        assert_eq!(split("a@x.com b@y.com"), ["a@x.com", "b@y.com"]);
+        assert_eq!(
+            split(" a@x.com,, b@y.com  c@z.com "),
+            ["a@x.com", "b@y.com", "c@z.com"]
+        );
+    }
+
+    #[test]
+    fn split_keeps_a_name_next_to_an_address_together() {
+        assert_eq!(split("Anna anna@acme.com"), ["Anna anna@acme.com"]);
+    }
+
+    #[test]
+    fn normalize_lowercases_domains_and_drops_repeats() {
+        let recipients = [
+            "Anna@ACME.com".to_string(),
+            "anna@acme.com, Bob Jones".to_string(),
+        ];
+        assert_eq!(normalize(&recipients), ["Anna@acme.com", "Bob Jones"]);
+    }
+}
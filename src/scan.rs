@@ -0,0 +1,43 @@
+//! Discovery of files holding secrets in a repository.
+
+use crate::sections::glob_match;
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+
+/// File names that usually hold secrets.
+pub const PATTERNS: &[&str] = &[".env*", "*.pem", "credentials*"];
+
+/// Whether a file called `name` usually holds secrets.
+pub fn is_secret_file(name: &str) -> bool {
+    PATTERNS.iter().any(|pattern| glob_match(pattern, name))
+}
+
+/// The files under `root` that usually hold secrets, sorted by path.
+///
+/// Directories ignored by git, such as `node_modules` or `target`, are not searched. Files in the
+/// searched directories are found even when ignored, since secrets usually are.
+pub fn find(root: &Path) -> Vec<PathBuf> {
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    let mut files = Vec::new();
+    for dir in walker.flatten() {
+        if !dir.file_type().is_some_and(|kind| kind.is_dir()) {
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(dir.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|kind| kind.is_file())
+                && is_secret_file(&entry.file_name().to_string_lossy())
+            {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    files
+}
@@ -0,0 +1,91 @@
+//! Grouping of structured fields into named sections of the item.
+
+use crate::error::{Result, ShareError};
+
+/// A section of the item holding the variables whose keys match one of its patterns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionRule {
+    /// The heading shown in 1Password.
+    pub label: String,
+    /// Glob patterns on variable keys, where `*` matches any run of characters and `?` one.
+    pub patterns: Vec<String>,
+}
+
+impl SectionRule {
+    /// Parse a rule written as `LABEL=PATTERN[,PATTERN...]`, e.g. `Database=DB_*,PG*`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || ShareError::InvalidSection {
+            spec: spec.to_string(),
+        };
+        let (label, patterns) = spec.split_once('=').ok_or_else(invalid)?;
+        let patterns: Vec<String> = patterns
+            .split(',')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(str::to_string)
+            .collect();
+        if label.trim().is_empty() || patterns.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            label: label.trim().to_string(),
+            patterns,
+        })
+    }
+
+    /// The section ID used in the item JSON, derived from the label.
+    pub fn id(&self) -> String {
+        section_id(&self.label)
+    }
+
+    /// Whether the variable `key` belongs in this section.
+    pub fn matches(&self, key: &str) -> bool {
+        self.patterns.iter().any(|pattern| glob_match(pattern, key))
+    }
+}
+
+/// A section ID for structured fields derived from `label`.
+pub fn section_id(label: &str) -> String {
+    let slug: String = label
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("env-{}", slug)
+}
+
+/// Match `text` against a glob `pattern` supporting `*` and `?`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was seen, and the text position it currently stands for
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,290 @@
+//! Manipulation of 1Password item templates.
+
+use serde_json::{json, Value};
+
+/// The item category used for shared notes.
+pub const SECURE_NOTE: &str = "Secure Note";
+
+/// The field holding the notes of an item, present in every category.
+pub const NOTES_FIELD: &str = "notesPlain";
+
+/// The kinds of item the shared content can be stored as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Category {
+    #[default]
+    SecureNote,
+    ApiCredential,
+    Password,
+    Login,
+    /// A file attachment, uploaded with `op document create` rather than from a template.
+    Document,
+}
+
+impl Category {
+    /// The category name 1Password uses for templates, e.g. `"API Credential"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::SecureNote => SECURE_NOTE,
+            Category::ApiCredential => "API Credential",
+            Category::Password => "Password",
+            Category::Login => "Login",
+            Category::Document => "Document",
+        }
+    }
+
+    /// The template field the content is stored in, or `None` for documents, which hold it as
+    /// their file.
+    pub fn content_field(self) -> Option<&'static str> {
+        match self {
+            Category::SecureNote => Some(NOTES_FIELD),
+            Category::ApiCredential => Some("credential"),
+            Category::Password | Category::Login => Some("password"),
+            Category::Document => None,
+        }
+    }
+}
+
+/// A template for `category` that does not require asking 1Password for it.
+///
+/// Used by backends that cannot fetch templates, such as 1Password Connect.
+pub fn builtin(category: &str) -> Option<Value> {
+    match category {
+        SECURE_NOTE => Some(json!({
+            "category": "SECURE_NOTE",
+            "fields": [{
+                "id": "notesPlain",
+                "type": "STRING",
+                "purpose": "NOTES",
+                "label": "notesPlain",
+                "value": ""
+            }]
+        })),
+        "API Credential" => Some(json!({
+            "category": "API_CREDENTIAL",
+            "fields": [
+                { "id": "credential", "type": "CONCEALED", "label": "credential", "value": "" },
+                notes_field(),
+            ]
+        })),
+        "Password" => Some(json!({
+            "category": "PASSWORD",
+            "fields": [
+                { "id": "password", "type": "CONCEALED", "purpose": "PASSWORD", "label": "password", "value": "" },
+                notes_field(),
+            ]
+        })),
+        "Login" => Some(json!({
+            "category": "LOGIN",
+            "fields": [
+                { "id": "username", "type": "STRING", "purpose": "USERNAME", "label": "username", "value": "" },
+                { "id": "password", "type": "CONCEALED", "purpose": "PASSWORD", "label": "password", "value": "" },
+                notes_field(),
+            ]
+        })),
+        _ => None,
+    }
+}
+
+fn notes_field() -> Value {
+    json!({
+        "id": NOTES_FIELD,
+        "type": "STRING",
+        "purpose": "NOTES",
+        "label": NOTES_FIELD,
+        "value": ""
+    })
+}
+
+/// Return a copy of `template` with the `notesPlain` field set to `content`.
+pub fn with_notes(template: &Value, content: &str) -> Value {
+    with_value(template, NOTES_FIELD, content)
+}
+
+/// Return a copy of `template` with the field `field_id` set to `content`.
+///
+/// Templates without that field are returned unchanged.
+pub fn with_value(template: &Value, field_id: &str, content: &str) -> Value {
+    let mut modified_template = template.clone();
+    if let Some(fields) = modified_template
+        .get_mut("fields")
+        .and_then(|f| f.as_array_mut())
+    {
+        for field in fields {
+            if field.get("id").and_then(|id| id.as_str()) == Some(field_id) {
+                field["value"] = content.into();
+            }
+        }
+    }
+    modified_template
+}
+
+/// The type of an item field, which controls how 1Password displays it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Text,
+    Concealed,
+    Url,
+    Email,
+}
+
+impl FieldType {
+    /// The type name used in item JSON.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FieldType::Text => "STRING",
+            FieldType::Concealed => "CONCEALED",
+            FieldType::Url => "URL",
+            FieldType::Email => "EMAIL",
+        }
+    }
+}
+
+/// A heading grouping fields of an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub id: String,
+    pub label: String,
+}
+
+impl Section {
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+        }
+    }
+}
+
+/// A field to add to an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub id: String,
+    pub label: String,
+    pub kind: FieldType,
+    pub value: String,
+    pub section: Option<Section>,
+}
+
+impl Field {
+    /// A field outside any section.
+    pub fn new(
+        id: impl Into<String>,
+        label: impl Into<String>,
+        kind: FieldType,
+        value: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            kind,
+            value: value.into(),
+            section: None,
+        }
+    }
+
+    /// Place the field in `section`.
+    pub fn in_section(mut self, section: Section) -> Self {
+        self.section = Some(section);
+        self
+    }
+}
+
+fn fields_mut(item: &mut Value) -> &mut Vec<Value> {
+    if !item.get("fields").is_some_and(Value::is_array) {
+        item["fields"] = json!([]);
+    }
+    item["fields"].as_array_mut().expect("fields is an array")
+}
+
+/// Add `field` to the item, along with its section if the item does not have it yet.
+pub fn add_field(item: &mut Value, field: Field) {
+    let mut json = json!({
+        "id": field.id,
+        "type": field.kind.as_str(),
+        "label": field.label,
+        "value": field.value
+    });
+
+    if let Some(section) = field.section {
+        if !item.get("sections").is_some_and(Value::is_array) {
+            item["sections"] = json!([]);
+        }
+        let sections = item["sections"]
+            .as_array_mut()
+            .expect("sections is an array");
+        if !sections
+            .iter()
+            .any(|existing| existing.get("id").and_then(|id| id.as_str()) == Some(&section.id))
+        {
+            sections.push(json!({ "id": section.id, "label": section.label }));
+        }
+        json["section"] = json!({ "id": section.id });
+    }
+
+    fields_mut(item).push(json);
+}
+
+/// Set the value of the field with the ID of `field` if the item has one, keeping its type, and
+/// add `field` otherwise.
+pub fn put_field(item: &mut Value, field: Field) {
+    match fields_mut(item)
+        .iter_mut()
+        .find(|existing| existing.get("id").and_then(|id| id.as_str()) == Some(&field.id))
+    {
+        Some(existing) => existing["value"] = field.value.into(),
+        None => add_field(item, field),
+    }
+}
+
+/// Set the value of the field with `id`, adding a text field labelled `label` if there is none.
+pub fn set_field(item: &mut Value, id: &str, label: &str, value: &str) {
+    match fields_mut(item)
+        .iter_mut()
+        .find(|field| field.get("id").and_then(|id| id.as_str()) == Some(id))
+    {
+        Some(field) => field["value"] = value.into(),
+        None => add_field(item, Field::new(id, label, FieldType::Text, value)),
+    }
+}
+
+/// The value of the field with `id`, if the item has one.
+pub fn field_value<'a>(item: &'a Value, id: &str) -> Option<&'a str> {
+    item.get("fields")?
+        .as_array()?
+        .iter()
+        .find(|field| field.get("id").and_then(|id| id.as_str()) == Some(id))?
+        .get("value")?
+        .as_str()
+}
+
+/// The value of the field whose ID or label is `name`, ignoring case, if the item has one.
+pub fn find_field_value<'a>(item: &'a Value, name: &str) -> Option<&'a str> {
+    let named = |field: &&Value, key: &str| {
+        field
+            .get(key)
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| value.eq_ignore_ascii_case(name))
+    };
+    let fields = item.get("fields")?.as_array()?;
+    fields
+        .iter()
+        .find(|field| named(field, "id"))
+        .or_else(|| fields.iter().find(|field| named(field, "label")))?
+        .get("value")?
+        .as_str()
+}
+
+/// Add `tags` to the item, keeping any it already has.
+pub fn add_tags(item: &mut Value, tags: &[&str]) {
+    let mut all: Vec<Value> = item
+        .get("tags")
+        .and_then(|tags| tags.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for tag in tags {
+        if !all.iter().any(|existing| existing.as_str() == Some(tag)) {
+            all.push((*tag).into());
+        }
+    }
+    item["tags"] = all.into();
+}
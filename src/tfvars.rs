@@ -0,0 +1,282 @@
+//! Parsing of Terraform variable definition files (`.tfvars`).
+//!
+//! Only the subset of HCL these files use is understood: `name = value` assignments of strings,
+//! heredocs, numbers, booleans, and lists or maps, with `#`, `//`, and `/* */` comments. Lists and
+//! maps are kept as written.
+
+use crate::dotenv::Variable;
+use crate::error::{Result, ShareError};
+
+/// The assignments in `content`, in file order.
+pub fn parse(content: &str) -> Result<Vec<Variable>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut variables = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let number = index + 1;
+        let line = lines[index].trim();
+        index += 1;
+
+        if let Some(mut rest) = line.strip_prefix("/*") {
+            // Skip to the end of the block comment
+            while !rest.contains("*/") {
+                let Some(next) = lines.get(index) else {
+                    return Err(error(number, "unterminated comment"));
+                };
+                rest = next;
+                index += 1;
+            }
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+
+        let Some((key, rest)) = line.split_once('=') else {
+            return Err(error(number, "expected name = value"));
+        };
+        let key = key.trim();
+        let key = key
+            .strip_prefix('"')
+            .and_then(|key| key.strip_suffix('"'))
+            .unwrap_or(key);
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+        {
+            return Err(error(number, format!("invalid name '{}'", key)));
+        }
+
+        let rest = rest.trim();
+        let value = if let Some(marker) = rest.strip_prefix("<<") {
+            let (value, end) = heredoc(&lines, number, marker)?;
+            index = end;
+            value
+        } else if let Some(string) = rest.strip_prefix('"') {
+            string_literal(string, number)?
+        } else if rest.starts_with(['[', '{']) {
+            let (value, end) = collection(&lines, number, rest)?;
+            index = end;
+            value
+        } else {
+            strip_comment(rest).to_string()
+        };
+        if value.is_empty() {
+            return Err(error(number, format!("missing value for '{}'", key)));
+        }
+
+        variables.push(Variable {
+            key: key.to_string(),
+            value,
+        });
+    }
+    Ok(variables)
+}
+
+fn error(line: usize, message: impl Into<String>) -> ShareError {
+    ShareError::InvalidInput {
+        format: "tfvars".to_string(),
+        message: format!("line {}: {}", line, message.into()),
+    }
+}
+
+/// An unquoted value up to a trailing comment.
+fn strip_comment(value: &str) -> &str {
+    let end = [value.find('#'), value.find("//")]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(value.len());
+    value[..end].trim_end()
+}
+
+/// A double-quoted string, given without its opening quote.
+fn string_literal(rest: &str, line: usize) -> Result<String> {
+    let mut value = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let trailing = strip_comment(chars.as_str()).trim();
+                if !trailing.is_empty() {
+                    return Err(error(
+                        line,
+                        format!("unexpected '{}' after string", trailing),
+                    ));
+                }
+                return Ok(value);
+            }
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some(escaped) => value.push(escaped),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    Err(error(line, "unterminated string"))
+}
+
+/// A heredoc whose marker (after `<<`) is on line `start`. `<<-` heredocs have their common
+/// indentation removed.
+///
+/// Returns the value and the index of the line after the closing marker.
+fn heredoc(lines: &[&str], start: usize, marker: &str) -> Result<(String, usize)> {
+    let (indented, marker) = match marker.strip_prefix('-') {
+        Some(marker) => (true, marker.trim()),
+        None => (false, marker.trim()),
+    };
+    if marker.is_empty() {
+        return Err(error(start, "missing heredoc marker"));
+    }
+
+    let mut body = Vec::new();
+    let mut index = start;
+    loop {
+        let Some(line) = lines.get(index) else {
+            return Err(error(
+                start,
+                format!("unterminated heredoc, expected {}", marker),
+            ));
+        };
+        index += 1;
+        if line.trim() == marker {
+            break;
+        }
+        body.push(*line);
+    }
+
+    if indented {
+        let indent = body
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        body = body
+            .iter()
+            .map(|line| line.get(indent..).unwrap_or(""))
+            .collect();
+    }
+    Ok((body.join("\n"), index))
+}
+
+/// A list or map starting on line `start`, kept as written up to its closing bracket.
+///
+/// Returns the value and the index of the line after the closing bracket.
+fn collection(lines: &[&str], start: usize, first: &str) -> Result<(String, usize)> {
+    let mut value = String::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut current = first;
+    let mut index = start;
+
+    loop {
+        let mut chars = current.chars();
+        while let Some(c) = chars.next() {
+            value.push(c);
+            match c {
+                '\\' if in_string => value.extend(chars.next()),
+                '"' => in_string = !in_string,
+                '[' | '{' if !in_string => depth += 1,
+                ']' | '}' if !in_string => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok((value, index));
+                    }
+                }
+                '#' if !in_string => {
+                    // A comment runs to the end of the line
+                    value.pop();
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let Some(next) = lines.get(index) else {
+            return Err(error(start, "unterminated list or map"));
+        };
+        value = value.trim_end().to_string();
+        value.push('\n');
+        current = next;
+        index += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(content: &str) -> Vec<(String, String)> {
+        parse(content)
+            .unwrap()
+            .into_iter()
+            .map(|variable| (variable.key, variable.value))
+            .collect()
+    }
+
+    #[test]
+    fn parse_reads_scalars_and_skips_comments() {
+        let content = "# comment\n// comment\n/* block\n comment */\nregion = \"eu-west-1\" # where\ncount = 3\n\"enabled\" = true\n";
+        assert_eq!(
+            pairs(content),
+            [
+                ("region".to_string(), "eu-west-1".to_string()),
+                ("count".to_string(), "3".to_string()),
+                ("enabled".to_string(), "true".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_reads_heredocs() {
+        let content =
+            "key = <<EOT\nline one\n  line two\nEOT\nindented = <<-EOT\n    a\n      b\n    EOT\n";
+        assert_eq!(
+            pairs(content),
+            [
+                ("key".to_string(), "line one\n  line two".to_string()),
+                ("indented".to_string(), "a\n  b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_keeps_collections_as_written() {
+        let content =
+            "tags = {\n  team = \"core\" # owner\n  note = \"a } b\"\n}\nzones = [\"a\", \"b\"]\n";
+        assert_eq!(
+            pairs(content),
+            [
+                (
+                    "tags".to_string(),
+                    "{\n  team = \"core\"\n  note = \"a } b\"\n}".to_string()
+                ),
+                ("zones".to_string(), "[\"a\", \"b\"]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_reports_errors() {
+        for content in [
+            "region",
+            "bad name = 1",
+            "key = \"open",
+            "key = <<EOT\nno end",
+            "list = [1,",
+            "key =",
+        ] {
+            assert!(
+                matches!(parse(content), Err(ShareError::InvalidInput { .. })),
+                "{:?}",
+                content
+            );
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Item title templates.
+//!
+//! A template is plain text with `{placeholder}` substitutions:
+//!
+//! - `{dir}`: name of the current directory
+//! - `{date}`: today's date as `dd.mm.yyyy`
+//! - `{time}`: the current time as `HH:MM`
+//! - `{user}`: the current user name
+//! - `{host}`: the machine's host name
+//! - `{branch}`: the checked-out git branch, empty outside a repository
+//! - `{%...}`: any strftime format, e.g. `{%Y-%m-%d}`
+
+use crate::error::{Result, ShareError};
+use crate::git;
+use chrono::format::{Item, StrftimeItems};
+use chrono::Local;
+
+/// The basename of the current directory, which names the project being shared from.
+pub fn project_name() -> Result<String> {
+    let current_dir = std::env::current_dir()?;
+    Ok(current_dir
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned())
+}
+
+/// Render `template` for the current directory and time.
+pub fn render(template: &str) -> Result<String> {
+    let now = Local::now();
+    let mut title = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        title.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+
+        match placeholder {
+            "dir" => title.push_str(&project_name()?),
+            "date" => title.push_str(&now.format("%d.%m.%Y").to_string()),
+            "time" => title.push_str(&now.format("%H:%M").to_string()),
+            "user" => title.push_str(&user()),
+            "host" => title.push_str(&gethostname::gethostname().to_string_lossy()),
+            "branch" => title.push_str(&git::branch(&std::env::current_dir()?).unwrap_or_default()),
+            format if format.starts_with('%') => {
+                let items: Vec<Item> = StrftimeItems::new(format).collect();
+                if items.contains(&Item::Error) {
+                    return Err(ShareError::InvalidTitleFormat {
+                        template: template.to_string(),
+                        placeholder: format.to_string(),
+                    });
+                }
+                title.push_str(&now.format_with_items(items.into_iter()).to_string());
+            }
+            unknown => {
+                return Err(ShareError::InvalidTitleFormat {
+                    template: template.to_string(),
+                    placeholder: unknown.to_string(),
+                })
+            }
+        }
+    }
+    title.push_str(rest);
+    Ok(title)
+}
+
+fn user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
@@ -0,0 +1,61 @@
+//! Copying items between vaults and accounts.
+
+use crate::error::{Result, ShareError};
+use crate::op::OpBackend;
+use serde_json::{Map, Value};
+
+/// Keys of an item's JSON that describe its content, and are kept when recreating it.
+const CONTENT_KEYS: &[&str] = &["title", "category", "sections", "fields", "urls", "tags"];
+
+/// An item as returned by 1Password, reduced to what recreating it elsewhere needs.
+///
+/// IDs, vault, version, and timestamps are dropped, as are field references, which point into
+/// the original vault.
+pub fn portable(item: &Value) -> Value {
+    let mut copy = Map::new();
+    for key in CONTENT_KEYS {
+        if let Some(value) = item.get(*key) {
+            copy.insert(key.to_string(), value.clone());
+        }
+    }
+    if let Some(fields) = copy.get_mut("fields").and_then(Value::as_array_mut) {
+        for field in fields.iter_mut().filter_map(Value::as_object_mut) {
+            field.remove("reference");
+        }
+    }
+    Value::Object(copy)
+}
+
+/// Recreate `item` from `from_vault` of `from` in `to_vault` of `to`, creating that vault if
+/// needed, with `tags` added.
+///
+/// Returns the ID and title of the new item. Documents cannot be copied, as their file is not
+/// part of the item.
+pub fn copy_item(
+    from: &dyn OpBackend,
+    item: &str,
+    from_vault: &str,
+    to: &dyn OpBackend,
+    to_vault: &str,
+    tags: &[&str],
+) -> Result<(String, String)> {
+    from.ensure_signed_in()?;
+    to.ensure_signed_in()?;
+    let original = from.get_item(item, from_vault)?;
+    if original.get("category").and_then(Value::as_str) == Some("DOCUMENT") {
+        return Err(ShareError::ItemCreate {
+            stderr: format!("'{}' is a document, which cannot be transferred", item),
+        });
+    }
+
+    let mut copy = portable(&original);
+    crate::template::add_tags(&mut copy, tags);
+    let title = copy
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or(item)
+        .to_string();
+    to.ensure_vault(to_vault)?;
+    let id = to.create_item(&title, to_vault, &copy)?;
+    Ok((id, title))
+}
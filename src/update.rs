@@ -0,0 +1,253 @@
+//! Updating the running binary from the GitHub releases of this project.
+//!
+//! A release carries one binary per platform, named `share-1password-<arch>-<os>` (with `.exe`
+//! on Windows), next to a `.sha256` file holding its SHA-256 checksum in `sha256sum` format and a
+//! `.sig` file holding its base64 Ed25519 signature. Both are made by the release workflow, which
+//! signs with a key only it holds and builds its binaries with the matching [`RELEASE_KEY`], so a
+//! release replaced by someone without the key is refused.
+
+use crate::error::{Result, ShareError};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::blocking::Client;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// The GitHub API endpoint describing the latest release.
+pub const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/magnusrodseth/share-1password/releases/latest";
+
+/// The base64 Ed25519 public key releases are signed with, set when the release workflow builds
+/// the binary. Builds without it, such as those of `cargo install`, cannot update themselves.
+pub const RELEASE_KEY: Option<&str> = option_env!("SHARE_1PASSWORD_RELEASE_KEY");
+
+/// The command that installs the latest release from crates.io.
+pub const INSTALL_COMMAND: &str = "cargo install share-1password";
+
+/// The version of the running binary.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A published release.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub assets: Vec<Asset>,
+}
+
+/// A file attached to a release.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    /// The version released, without the `v` prefix of its tag.
+    pub fn version(&self) -> &str {
+        self.tag_name.strip_prefix('v').unwrap_or(&self.tag_name)
+    }
+
+    /// Whether the release is newer than the running binary.
+    pub fn is_newer(&self) -> bool {
+        match (
+            parse_version(self.version()),
+            parse_version(CURRENT_VERSION),
+        ) {
+            (Some(released), Some(current)) => released > current,
+            _ => false,
+        }
+    }
+
+    /// Download the binary for this platform and check it against its published checksum and
+    /// its signature by [`RELEASE_KEY`].
+    pub fn download(&self) -> Result<Vec<u8>> {
+        let key = release_key()?;
+        let name = asset_name();
+        let asset = self.asset(&name)?;
+        let checksum = self.asset(&format!("{}.sha256", name))?;
+        let signature = self.asset(&format!("{}.sig", name))?;
+
+        let client = client()?;
+        let expected = String::from_utf8_lossy(&get(&client, &checksum.browser_download_url)?)
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let binary = get(&client, &asset.browser_download_url)?;
+        let actual: String = Sha256::digest(&binary)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        if actual != expected {
+            return Err(error(format!(
+                "the checksum of {} does not match the published one, not installing it",
+                name
+            )));
+        }
+        let signature = get(&client, &signature.browser_download_url)?;
+        if !verify(&key, &binary, &signature) {
+            return Err(error(format!(
+                "{} is not signed with the release key, not installing it",
+                name
+            )));
+        }
+        Ok(binary)
+    }
+
+    fn asset(&self, name: &str) -> Result<&Asset> {
+        self.assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| error(format!("release {} has no {}", self.tag_name, name)))
+    }
+}
+
+/// Look up the latest release.
+pub fn latest() -> Result<Release> {
+    let body = get(&client()?, LATEST_RELEASE_URL)?;
+    serde_json::from_slice(&body).map_err(|err| error(format!("unexpected release data: {}", err)))
+}
+
+/// Replace the running binary with `binary`, returning its path.
+pub fn install(binary: &[u8]) -> Result<PathBuf> {
+    let path = std::env::current_exe()?;
+    let dir = path
+        .parent()
+        .ok_or_else(|| error(format!("cannot replace {}", path.display())))?;
+
+    // Written next to the binary, so the rename that replaces it stays on one file system
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(binary)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tmp.as_file()
+            .set_permissions(std::fs::Permissions::from_mode(0o755))?;
+    }
+    // A running binary cannot be overwritten on Windows, but it can be moved out of the way
+    #[cfg(windows)]
+    std::fs::rename(&path, path.with_extension("old.exe"))?;
+    tmp.persist(&path).map_err(|err| err.error)?;
+    Ok(path)
+}
+
+/// The name of the release asset for this platform, e.g. `share-1password-x86_64-linux`.
+pub fn asset_name() -> String {
+    format!(
+        "share-1password-{}-{}{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+/// The decoded [`RELEASE_KEY`].
+fn release_key() -> Result<Vec<u8>> {
+    let key = RELEASE_KEY.ok_or_else(|| {
+        error(format!(
+            "this build cannot check the signature of releases, update it with `{}` instead",
+            INSTALL_COMMAND
+        ))
+    })?;
+    BASE64
+        .decode(key.trim())
+        .map_err(|err| error(format!("the release key is not valid base64: {}", err)))
+}
+
+/// Whether `signature`, in base64, is the Ed25519 signature of `binary` by the public `key`.
+fn verify(key: &[u8], binary: &[u8], signature: &[u8]) -> bool {
+    let Ok(signature) = BASE64.decode(String::from_utf8_lossy(signature).trim()) else {
+        return false;
+    };
+    UnparsedPublicKey::new(&ED25519, key)
+        .verify(binary, &signature)
+        .is_ok()
+}
+
+fn client() -> Result<Client> {
+    // GitHub refuses API requests without a user agent
+    Client::builder()
+        .user_agent(format!("share-1password/{}", CURRENT_VERSION))
+        .build()
+        .map_err(|err| error(err.to_string()))
+}
+
+fn get(client: &Client, url: &str) -> Result<Vec<u8>> {
+    let response = client
+        .get(url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| error(err.to_string()))?;
+    Ok(response
+        .bytes()
+        .map_err(|err| error(err.to_string()))?
+        .to_vec())
+}
+
+/// A `MAJOR.MINOR.PATCH` version, ignoring pre-release and build suffixes.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let release = version.split(['-', '+']).next()?;
+    let mut parts = release.split('.').map(|part| part.parse().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+fn error(message: impl Into<String>) -> ShareError {
+    ShareError::Update {
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn verify_accepts_the_signature_of_the_release_key() {
+        let pair = key_pair();
+        let signature = BASE64.encode(pair.sign(b"binary"));
+        assert!(verify(
+            pair.public_key().as_ref(),
+            b"binary",
+            signature.as_bytes()
+        ));
+    }
+
+    #[test]
+    fn verify_refuses_a_changed_binary_or_another_key() {
+        let pair = key_pair();
+        let signature = BASE64.encode(pair.sign(b"binary"));
+        assert!(!verify(
+            pair.public_key().as_ref(),
+            b"binarx",
+            signature.as_bytes()
+        ));
+        assert!(!verify(
+            key_pair().public_key().as_ref(),
+            b"binary",
+            signature.as_bytes()
+        ));
+        assert!(!verify(
+            pair.public_key().as_ref(),
+            b"binary",
+            b"not base64!"
+        ));
+    }
+
+    #[test]
+    fn parse_version_ignores_suffixes() {
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.10.0-beta.1+build"), Some((1, 10, 0)));
+        assert_eq!(parse_version("1.2"), None);
+        assert_eq!(parse_version("v1.2.3"), None);
+    }
+}